@@ -1,7 +1,7 @@
 use actix_web::{get, web::Data, App, HttpRequest, HttpServer, Responder};
 use inertia_rust::actix::{render_with_props, InertiaMiddleware};
 use inertia_rust::{
-    Inertia, InertiaConfig, InertiaProp, InertiaService, InertiaVersion, SsrClient,
+    Inertia, InertiaConfig, InertiaProp, InertiaService, InertiaVersion, PropResolver, SsrClient,
 };
 use serde_json::json;
 use std::collections::HashMap;
@@ -77,7 +77,7 @@ async fn main() -> std::io::Result<()> {
                     shared_props.insert("version".into(), InertiaProp::Always("0.1.0".into()));
                     shared_props.insert(
                         "assetsVersion".into(),
-                        InertiaProp::Lazy(Arc::new(move || {
+                        InertiaProp::Lazy(PropResolver::new(move || {
                             serde_json::to_value(vite.get_hash().unwrap().to_string()).unwrap()
                         })),
                     );
@@ -0,0 +1,178 @@
+#![allow(clippy::doc_overindented_list_items)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+/// The `InertiaProp` variant a struct field is converted into.
+enum PropKind {
+    Data,
+    Always,
+    Lazy,
+    Demand,
+}
+
+struct FieldOptions {
+    kind: PropKind,
+    rename: Option<String>,
+}
+
+/// Derives `inertia_rust::IntoInertiaProps` for a struct with named fields, so that it can be
+/// converted into `InertiaProps`. Every field must implement `serde::Serialize`.
+///
+/// Each field is converted into an `InertiaProp::Data` by default. The `#[inertia(...)]` field
+/// attribute changes it:
+/// * `always`          -   Converts the field into an `InertiaProp::Always`.
+/// * `lazy`            -   Converts the field into an `InertiaProp::Lazy`. The field is only
+///                         serialized if it's going to be sent, and it must also be
+///                         `Send + Sync + 'static`.
+/// * `demand`          -   Converts the field into an `InertiaProp::Demand`. Same requirements
+///                         as `lazy`.
+/// * `rename = "..."`  -   Sets the prop key. Defaults to the field name.
+///
+/// # Examples
+/// ```ignore
+/// use inertia_rust::{InertiaProps, IntoInertiaProps};
+///
+/// #[derive(InertiaProps)]
+/// struct DashboardProps {
+///     #[inertia(always)]
+///     title: String,
+///     #[inertia(demand, rename = "dailyStats")]
+///     daily_stats: Vec<u32>,
+/// }
+/// ```
+#[proc_macro_derive(InertiaProps, attributes(inertia))]
+pub fn derive_inertia_props(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand_inertia_props(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand_inertia_props(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "InertiaProps can only be derived for structs with named fields.",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "InertiaProps can only be derived for structs.",
+            ))
+        }
+    };
+
+    let mut inserts = Vec::new();
+
+    for field in fields.iter() {
+        let ident = field.ident.as_ref().unwrap();
+        let options = parse_field_options(field)?;
+        let key = options.rename.unwrap_or_else(|| ident.to_string());
+
+        let prop = match options.kind {
+            PropKind::Data => quote! {
+                ::inertia_rust::InertiaProp::Data(__to_value(self.#ident)?)
+            },
+            PropKind::Always => quote! {
+                ::inertia_rust::InertiaProp::Always(__to_value(self.#ident)?)
+            },
+            PropKind::Lazy => quote! {
+                ::inertia_rust::InertiaProp::Lazy(__resolver(self.#ident))
+            },
+            PropKind::Demand => quote! {
+                ::inertia_rust::InertiaProp::Demand(__resolver(self.#ident))
+            },
+        };
+
+        inserts.push(quote! {
+            props.insert(#key.to_string(), #prop);
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::inertia_rust::IntoInertiaProps for #name #ty_generics #where_clause {
+            fn into_inertia_props(
+                self,
+            ) -> ::std::result::Result<::inertia_rust::InertiaProps, ::inertia_rust::InertiaError> {
+                use ::inertia_rust::__private::{serde, serde_json};
+
+                fn __to_value<T: serde::Serialize>(
+                    value: T,
+                ) -> ::std::result::Result<serde_json::Value, ::inertia_rust::InertiaError> {
+                    serde_json::to_value(value).map_err(|err| {
+                        ::inertia_rust::InertiaError::SerializationError(err.to_string())
+                    })
+                }
+
+                fn __resolver<T>(value: T) -> ::inertia_rust::PropResolver
+                where
+                    T: serde::Serialize + Send + Sync + 'static,
+                {
+                    ::inertia_rust::PropResolver::new_fallible(move || serde_json::to_value(&value))
+                }
+
+                let mut props = ::inertia_rust::InertiaProps::new();
+                #(#inserts)*
+                Ok(props)
+            }
+        }
+    })
+}
+
+fn parse_field_options(field: &syn::Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions {
+        kind: PropKind::Data,
+        rename: None,
+    };
+
+    let mut kind_is_set = false;
+
+    for attr in field.attrs.iter() {
+        if !attr.path().is_ident("inertia") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            let kind = if meta.path.is_ident("always") {
+                PropKind::Always
+            } else if meta.path.is_ident("lazy") {
+                PropKind::Lazy
+            } else if meta.path.is_ident("demand") {
+                PropKind::Demand
+            } else if meta.path.is_ident("rename") {
+                let key: LitStr = meta.value()?.parse()?;
+                options.rename = Some(key.value());
+                return Ok(());
+            } else {
+                return Err(meta.error(
+                    "unknown inertia attribute. Expected `always`, `lazy`, `demand` or `rename`.",
+                ));
+            };
+
+            if kind_is_set {
+                return Err(
+                    meta.error("a field can only have one of `always`, `lazy` or `demand`.")
+                );
+            }
+
+            options.kind = kind;
+            kind_is_set = true;
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
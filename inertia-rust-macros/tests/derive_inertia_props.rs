@@ -0,0 +1,127 @@
+use actix_web::{get, web::Data, App, HttpRequest, Responder};
+use inertia_rust::actix::{render_with_props, InertiaHeader};
+use inertia_rust::{
+    Component, Inertia, InertiaConfig, InertiaPage, InertiaProp, InertiaProps, InertiaVersion,
+    IntoInertiaProps, TemplateResolverOutput, ViewData,
+};
+use serde::Serialize;
+use serde_json::json;
+
+const VERSION: &str = "v1.0.0";
+
+#[derive(Serialize)]
+struct User {
+    name: String,
+}
+
+#[derive(InertiaProps)]
+struct UsersIndexProps {
+    title: String,
+    #[inertia(always)]
+    auth: Option<User>,
+    #[inertia(lazy, rename = "allUsers")]
+    users: Vec<User>,
+    #[inertia(demand)]
+    permissions: Vec<String>,
+}
+
+fn users_index_props() -> UsersIndexProps {
+    UsersIndexProps {
+        title: "Users".into(),
+        auth: None,
+        users: vec![User {
+            name: "John Doe".into(),
+        }],
+        permissions: vec!["users.create".into()],
+    }
+}
+
+fn template_resolver(
+    _path: &'static str,
+    view_data: ViewData,
+    _data: &'static (),
+) -> TemplateResolverOutput {
+    Box::pin(async move { Ok(serde_json::to_string(&view_data.page).unwrap()) })
+}
+
+#[get("/users")]
+async fn users_index(req: HttpRequest) -> impl Responder {
+    let props = users_index_props().into_inertia_props().unwrap();
+    render_with_props::<()>(&req, Component("Users/Index".into()), props).await
+}
+
+#[test]
+fn test_derive_inertia_props() {
+    let props = users_index_props().into_inertia_props().unwrap();
+
+    let mut keys = props.keys().cloned().collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(keys, vec!["allUsers", "auth", "permissions", "title"]);
+
+    assert!(
+        matches!(props.get("title"), Some(InertiaProp::Data(title)) if title == &json!("Users"))
+    );
+    assert!(matches!(props.get("auth"), Some(InertiaProp::Always(auth)) if auth.is_null()));
+    assert!(matches!(props.get("allUsers"), Some(InertiaProp::Lazy(_))));
+    assert!(matches!(
+        props.get("permissions"),
+        Some(InertiaProp::Demand(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_resolve_derived_props() {
+    let inertia = Inertia::new(
+        InertiaConfig::builder()
+            .set_url("https://inertiajs.com")
+            .set_version(InertiaVersion::Literal(VERSION))
+            .set_template_path("")
+            .set_template_resolver(&template_resolver)
+            .set_template_resolver_data(&())
+            .build(),
+    )
+    .unwrap();
+
+    let app =
+        actix_web::test::init_service(App::new().app_data(Data::new(inertia)).service(users_index))
+            .await;
+
+    // standard visits leave the demand props out
+    let req = actix_web::test::TestRequest::get()
+        .uri("/users")
+        .insert_header(InertiaHeader::Inertia.convert())
+        .insert_header(InertiaHeader::Version(VERSION).convert())
+        .to_request();
+
+    let page: InertiaPage =
+        actix_web::test::read_body_json(actix_web::test::call_service(&app, req).await).await;
+
+    assert_eq!(
+        &json!({
+            "title": "Users",
+            "auth": null,
+            "allUsers": [{"name": "John Doe"}]
+        }),
+        &json!(page.get_props())
+    );
+
+    // partial reloads include the requested demand props, and skip the non-requested lazy ones
+    let req = actix_web::test::TestRequest::get()
+        .uri("/users")
+        .insert_header(InertiaHeader::Inertia.convert())
+        .insert_header(InertiaHeader::Version(VERSION).convert())
+        .insert_header(("X-Inertia-Partial-Component", "Users/Index"))
+        .insert_header(("X-Inertia-Partial-Data", "permissions"))
+        .to_request();
+
+    let page: InertiaPage =
+        actix_web::test::read_body_json(actix_web::test::call_service(&app, req).await).await;
+
+    assert_eq!(
+        &json!({
+            "auth": null,
+            "permissions": ["users.create"]
+        }),
+        &json!(page.get_props())
+    );
+}
@@ -1,8 +1,9 @@
 use crate::{
     inertia::{ReflashSession, TemplateResolver},
-    InertiaVersion, SsrClient,
+    InertiaVersion, PropKeysCase, PropsCache, PropsMergeStrategy, SsrClient,
 };
 use serde_json::{Map, Value};
+use std::sync::Arc;
 
 /// A configuration struct for initializing Inertia. You can directly fill the struct or use
 /// the builder fluent syntax by calling `InertiaConfig::builder()`, and finally `InertiaConfig::build()`.
@@ -28,8 +29,26 @@ use serde_json::{Map, Value};
 ///                                 be used.
 /// * `view_data`               -   Optional view data to be passed to the root template. It must be
 ///                                 handled by the provided `template_resolver`.
+/// * `encrypt_history`         -   Whether the pages' history states should be encrypted by the
+///                                 client-side adapter. It can be overridden per response.
+/// * `props_merge_strategy`    -   How shared props and page props with the same key are merged.
+///                                 Defaults to [`PropsMergeStrategy::PageWins`].
+/// * `props_cache`             -   The store of the cached props' values. If `None` is given, an
+///                                 [`InMemoryPropsCache`] is used.
+/// * `prop_keys_case`          -   The case the props' keys are converted to when the page is
+///                                 built. If `None` is given, keys are sent as they are.
+/// * `transform_nested_prop_keys`  -   Whether the keys of the objects nested in the props are
+///                                     converted too.
+/// * `page_soft_size_limit`    -   The serialized page size, in bytes, past which a warning listing
+///                                 the largest props is logged.
+/// * `page_hard_size_limit`    -   The serialized page size, in bytes, past which rendering fails
+///                                 with an [`InertiaError::PageSizeError`].
+/// * `default_back_url`        -   The URL `back` redirects to when the previous URL is unknown.
+///                                 If `None` is given, `/` is used.
 ///
 /// [`Inertia::template_resolver`]: crate::inertia::Inertia
+/// [`InMemoryPropsCache`]: crate::InMemoryPropsCache
+/// [`InertiaError::PageSizeError`]: crate::InertiaError::PageSizeError
 pub struct InertiaConfig<T, V>
 where
     T: 'static,
@@ -44,6 +63,14 @@ where
     pub custom_ssr_client: Option<SsrClient>,
     pub view_data: Option<Map<String, Value>>,
     pub reflash_inertia_session: ReflashSession,
+    pub encrypt_history: bool,
+    pub props_merge_strategy: PropsMergeStrategy,
+    pub props_cache: Option<Arc<dyn PropsCache>>,
+    pub prop_keys_case: Option<PropKeysCase>,
+    pub transform_nested_prop_keys: bool,
+    pub page_soft_size_limit: Option<usize>,
+    pub page_hard_size_limit: Option<usize>,
+    pub default_back_url: Option<&'static str>,
 }
 
 impl<T, V> InertiaConfig<T, V>
@@ -92,6 +119,14 @@ where
     pub custom_ssr_client: Option<SsrClient>,
     pub view_data: Option<Map<String, Value>>,
     pub reflash_inertia_session: Option<ReflashSession>,
+    pub encrypt_history: bool,
+    pub props_merge_strategy: PropsMergeStrategy,
+    pub props_cache: Option<Arc<dyn PropsCache>>,
+    pub prop_keys_case: Option<PropKeysCase>,
+    pub transform_nested_prop_keys: bool,
+    pub page_soft_size_limit: Option<usize>,
+    pub page_hard_size_limit: Option<usize>,
+    pub default_back_url: Option<&'static str>,
 }
 
 impl<T, V> Default for InertiaConfigBuilder<T, V>
@@ -142,6 +177,14 @@ where
             with_ssr: false,
             custom_ssr_client: None,
             reflash_inertia_session: None,
+            encrypt_history: false,
+            props_merge_strategy: PropsMergeStrategy::default(),
+            props_cache: None,
+            prop_keys_case: None,
+            transform_nested_prop_keys: false,
+            page_soft_size_limit: None,
+            page_hard_size_limit: None,
+            default_back_url: None,
         }
     }
 
@@ -190,6 +233,62 @@ where
         self
     }
 
+    /// Makes every page's history state be encrypted by the client-side adapter, unless
+    /// the response overrides it.
+    pub fn encrypt_history(mut self) -> Self {
+        self.encrypt_history = true;
+        self
+    }
+
+    /// Sets how shared props and page props with the same key are merged.
+    /// Defaults to [`PropsMergeStrategy::PageWins`].
+    pub fn set_props_merge_strategy(mut self, strategy: PropsMergeStrategy) -> Self {
+        self.props_merge_strategy = strategy;
+        self
+    }
+
+    /// Sets the store of the cached props' values.
+    pub fn set_props_cache(mut self, props_cache: Arc<dyn PropsCache>) -> Self {
+        self.props_cache = Some(props_cache);
+        self
+    }
+
+    /// Converts the props' keys to the given case when the pages are built (e.g., the Rust
+    /// `snake_case` keys to the javascript `camelCase`). The keys requested by partial reloads
+    /// are matched against the converted keys.
+    pub fn set_prop_keys_case(mut self, case: PropKeysCase) -> Self {
+        self.prop_keys_case = Some(case);
+        self
+    }
+
+    /// Makes the keys of the objects nested in the props be converted too, if a case is set
+    /// with [`InertiaConfigBuilder::set_prop_keys_case`].
+    pub fn transform_nested_prop_keys(mut self) -> Self {
+        self.transform_nested_prop_keys = true;
+        self
+    }
+
+    /// Logs a warning listing the largest props whenever a serialized page is larger than
+    /// `bytes`. Browsers limit the size of the history states, so pages that are too large may
+    /// break the back-navigation.
+    pub fn set_page_soft_size_limit(mut self, bytes: usize) -> Self {
+        self.page_soft_size_limit = Some(bytes);
+        self
+    }
+
+    /// Makes rendering fail with an [`crate::InertiaError::PageSizeError`] whenever a
+    /// serialized page is larger than `bytes`.
+    pub fn set_page_hard_size_limit(mut self, bytes: usize) -> Self {
+        self.page_hard_size_limit = Some(bytes);
+        self
+    }
+
+    /// Sets the URL users are redirected back to when the previous URL is unknown.
+    pub fn set_default_back_url(mut self, url: &'static str) -> Self {
+        self.default_back_url = Some(url);
+        self
+    }
+
     /// Compile the current `InertiaConfigBuilder` into a valid `InertiaConfig` struct.
     ///
     /// # Panics
@@ -235,13 +334,23 @@ where
             with_ssr: self.with_ssr,
             custom_ssr_client: self.custom_ssr_client,
             reflash_inertia_session: self.reflash_inertia_session.unwrap_or(Box::new(|_| Ok(()))),
+            encrypt_history: self.encrypt_history,
+            props_merge_strategy: self.props_merge_strategy,
+            props_cache: self.props_cache,
+            prop_keys_case: self.prop_keys_case,
+            transform_nested_prop_keys: self.transform_nested_prop_keys,
+            page_soft_size_limit: self.page_soft_size_limit,
+            page_hard_size_limit: self.page_hard_size_limit,
+            default_back_url: self.default_back_url,
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{InertiaError, InertiaVersion, TemplateResolverOutput, ViewData};
+    use crate::{
+        InertiaError, InertiaVersion, PropsMergeStrategy, TemplateResolverOutput, ViewData,
+    };
     use std::panic;
 
     use super::{InertiaConfig, InertiaConfigBuilder};
@@ -359,6 +468,14 @@ mod test {
             with_ssr: false,
             custom_ssr_client: None,
             reflash_inertia_session: Box::new(|_| Ok(())),
+            encrypt_history: false,
+            props_merge_strategy: PropsMergeStrategy::PageWins,
+            props_cache: None,
+            prop_keys_case: None,
+            transform_nested_prop_keys: false,
+            page_soft_size_limit: None,
+            page_hard_size_limit: None,
+            default_back_url: None,
         };
 
         assert_eq!(&with_builder.url, &directly_initialized.url);
@@ -380,6 +497,14 @@ mod test {
             &with_builder.custom_ssr_client,
             &directly_initialized.custom_ssr_client
         );
+        assert_eq!(
+            &with_builder.encrypt_history,
+            &directly_initialized.encrypt_history
+        );
+        assert_eq!(
+            &with_builder.props_merge_strategy,
+            &directly_initialized.props_merge_strategy
+        );
     }
 
     // endregion: --- Tests
@@ -3,12 +3,22 @@ use std::{error::Error, fmt, io};
 use crate::node_process::NodeJsError;
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum InertiaError {
     SerializationError(String),
     HeaderError(String),
     SsrError(String),
     RenderError(String),
     NodeJsError(NodeJsError),
+    /// A prop resolver has failed. Holds the prop key and the resolver's error message.
+    PropError(String, String),
+    /// The serialized page exceeds the configured hard size limit.
+    PageSizeError(String),
+    /// A redirect URL is invalid.
+    UrlError(String),
+    /// A shared prop and a page prop have the same key, and the props merge strategy forbids
+    /// it. Holds the prop key.
+    PropsConflictError(String),
 }
 
 impl fmt::Display for InertiaError {
@@ -29,10 +39,18 @@ impl InertiaError {
             InertiaError::SerializationError(err) => err.clone(),
             InertiaError::SsrError(err) => err.clone(),
             InertiaError::RenderError(err) => err.clone(),
+            InertiaError::PropError(key, err) => {
+                format!("Failed to resolve prop \"{}\": {}", key, err)
+            }
+            InertiaError::PageSizeError(err) => err.clone(),
+            InertiaError::UrlError(err) => err.clone(),
+            InertiaError::PropsConflictError(key) => {
+                format!("Prop \"{}\" is both a shared prop and a page prop", key)
+            }
         }
     }
 
     pub fn to_io_error(self) -> io::Error {
-        io::Error::new(io::ErrorKind::Other, self.get_cause())
+        io::Error::other(self.get_cause())
     }
 }
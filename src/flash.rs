@@ -0,0 +1,39 @@
+use crate::InertiaError;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Data flashed by a redirect, so that it's merged into the props of the next request's page
+/// by the Inertia middleware.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FlashData {
+    /// Validation errors, merged into the `errors` prop.
+    pub errors: Map<String, Value>,
+    /// Any other data (e.g., a success message), sent as the `flash` prop.
+    pub flash: Map<String, Value>,
+}
+
+impl FlashData {
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.flash.is_empty()
+    }
+}
+
+/// A store that keeps the [`FlashData`] of a client between a redirect and the next request.
+/// It's usually backed by the session manager of your HTTP framework, which knows the client
+/// the request comes from.
+///
+/// `THttpRequest` is the HTTP request of the framework opted by the cargo feature.
+pub trait FlashStore<THttpRequest>: Send + Sync {
+    /// Stores the data flashed by a redirect, replacing any data not pulled yet.
+    ///
+    /// # Errors
+    /// The error is turned into an error response instead of the redirect.
+    fn put(&self, req: &THttpRequest, data: FlashData) -> Result<(), InertiaError>;
+
+    /// Takes the data flashed to the client the request comes from, if there is any. Once
+    /// pulled, the data must be removed from the store.
+    ///
+    /// # Errors
+    /// The error is only logged as a warning, and the request is handled without flashed data.
+    fn pull(&self, req: &THttpRequest) -> Result<Option<FlashData>, InertiaError>;
+}
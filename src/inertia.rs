@@ -1,11 +1,15 @@
 use std::future::Future;
 use std::io;
 use std::pin::Pin;
+use std::sync::Arc;
 
 use crate::config::InertiaConfig;
 use crate::node_process::NodeJsProc;
-use crate::props::InertiaProps;
+use crate::prop_keys::PropKeysTransform;
+use crate::props::{InertiaProps, PropsMergeStrategy};
+use crate::props_cache::{InMemoryPropsCache, PropsCache};
 use crate::req_type::InertiaRequestType;
+use crate::utils::{convert_struct_to_json_bytes, inertia_err_msg};
 use crate::{InertiaError, InertiaPage, InertiaSSRPage, InertiaTemporarySession};
 use async_trait::async_trait;
 use reqwest::Url;
@@ -24,6 +28,20 @@ pub const X_INERTIA_PARTIAL_COMPONENT: &str = "x-inertia-partial-component";
 pub const X_INERTIA_PARTIAL_DATA: &str = "x-inertia-partial-data";
 #[allow(unused)]
 pub const X_INERTIA_PARTIAL_EXCEPT: &str = "x-inertia-partial-except";
+#[allow(unused)]
+pub const X_INERTIA_RESET: &str = "x-inertia-reset";
+#[allow(unused)]
+pub const X_INERTIA_EXCEPT_ONCE_PROPS: &str = "x-inertia-except-once-props";
+#[allow(unused)]
+pub const X_INERTIA_ERROR_BAG: &str = "x-inertia-error-bag";
+#[allow(unused)]
+pub const X_INERTIA_INFINITE_SCROLL_MERGE_INTENT: &str = "x-inertia-infinite-scroll-merge-intent";
+
+#[allow(unused)]
+pub const PURPOSE: &str = "purpose";
+/// The `Purpose` header value sent by the client-side adapter when prefetching a page.
+#[allow(unused)]
+pub const PREFETCH_PURPOSE: &str = "prefetch";
 
 /// The javascript component name.
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
@@ -96,6 +114,38 @@ pub trait InertiaResponder<TResponder, THttpRequest> {
         props: InertiaProps,
     ) -> Result<TResponder, InertiaError>;
 
+    /// Renders an Inertia Page as an HTTP response, taking its props from a serializable
+    /// struct. Every field of the struct becomes an `InertiaProp::Data` prop.
+    ///
+    /// # Arguments
+    /// * `req`         -   The HTTP request.
+    /// * `component`   -   The page component to be rendered by the client-side adapter.
+    /// * `props`       -   A `TProps` (serializable) struct containing
+    ///                     the props to be sent to the client-side.
+    ///
+    /// # Errors
+    /// Returns an [`InertiaError::SerializationError`] if `props` can't be serialized
+    /// as a JSON object.
+    async fn render_with_struct<TProps>(
+        &self,
+        req: &THttpRequest,
+        component: Component,
+        props: TProps,
+    ) -> Result<TResponder, InertiaError>
+    where
+        TProps: Serialize;
+
+    /// Redirects the user back to the previous page. The URL is taken from the
+    /// [`InertiaTemporarySession::prev_req_url`], if there is a temporary session, then from
+    /// the `Referer` header and, at last, from the configured `default_back_url`.
+    ///
+    /// Non-GET requests are redirected with a `303 See Other` status, so that the client
+    /// follows the redirect with a GET request. E.g., after a form submission fails.
+    ///
+    /// # Arguments
+    /// * `req`     - A reference to the HTTP request.
+    fn back(&self, req: &THttpRequest) -> TResponder;
+
     /// Provokes a client-side redirect to an extern URL.
     ///
     /// # Arguments
@@ -111,6 +161,17 @@ pub(crate) trait InertiaHttpRequest {
 
     fn get_request_type(&self) -> Result<InertiaRequestType, InertiaError>;
 
+    /// Checks whether the client-side adapter is prefetching the page (e.g., when a link is
+    /// hovered) rather than visiting it.
+    fn is_prefetch_request(&self) -> bool;
+
+    /// Gets the keys of the once props the client-side adapter already holds.
+    fn get_except_once_props(&self) -> Result<Vec<String>, InertiaError>;
+
+    /// Gets the keys of the mergeable props that must be replaced rather than merged. Any
+    /// Inertia request may ask for them, not only partial reloads.
+    fn get_reset_props(&self) -> Result<Vec<String>, InertiaError>;
+
     fn check_inertia_version(&self, current_version: &str) -> bool;
 }
 
@@ -134,6 +195,31 @@ where
     }
 }
 
+fn serialized_size<T>(value: &T) -> Result<usize, InertiaError>
+where
+    T: Serialize,
+{
+    convert_struct_to_json_bytes(value).map(|bytes| bytes.len())
+}
+
+/// Lists the five largest props of the page, by their serialized size.
+fn largest_props(page: &InertiaPage) -> Result<String, InertiaError> {
+    let mut sizes = Vec::new();
+
+    for (key, value) in page.get_props().iter() {
+        sizes.push((key, serialized_size(value)?));
+    }
+
+    sizes.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    Ok(sizes
+        .iter()
+        .take(5)
+        .map(|(key, size)| format!("\"{}\" ({} bytes)", key, size))
+        .collect::<Vec<_>>()
+        .join(", "))
+}
+
 /// View Data is a struct containing props to be used by the root template.
 pub struct ViewData {
     pub page: InertiaPage,
@@ -233,6 +319,20 @@ where
     /// You can return an `InertiaError` from this method if you desire, however, all Inertia will do with
     /// this error is log it as a warning. It won't stop the rendering method from refreshing the request.
     pub(crate) reflash_inertia_session: ReflashSession,
+    /// Whether pages' history states should be encrypted by default.
+    pub(crate) encrypt_history: bool,
+    /// How shared props and page props with the same key are merged.
+    pub(crate) props_merge_strategy: PropsMergeStrategy,
+    /// The store of the cached props' values.
+    pub(crate) props_cache: Arc<dyn PropsCache>,
+    /// How the props' keys are renamed before the page is built, if they are.
+    pub(crate) prop_keys_transform: Option<PropKeysTransform>,
+    /// The serialized page size, in bytes, past which a warning is logged.
+    pub(crate) page_soft_size_limit: Option<usize>,
+    /// The serialized page size, in bytes, past which rendering fails.
+    pub(crate) page_hard_size_limit: Option<usize>,
+    /// The URL users are redirected back to when the previous URL is unknown.
+    pub(crate) default_back_url: &'static str,
 }
 
 impl<T> Inertia<T>
@@ -293,13 +393,87 @@ where
             ssr_url,
             custom_view_data: config.view_data.unwrap_or_default(),
             reflash_inertia_session: config.reflash_inertia_session,
+            encrypt_history: config.encrypt_history,
+            props_merge_strategy: config.props_merge_strategy,
+            props_cache: config
+                .props_cache
+                .unwrap_or_else(|| Arc::new(InMemoryPropsCache::default())),
+            prop_keys_transform: config.prop_keys_case.map(|case| PropKeysTransform {
+                case,
+                recursive: config.transform_nested_prop_keys,
+            }),
+            page_soft_size_limit: config.page_soft_size_limit,
+            page_hard_size_limit: config.page_hard_size_limit,
+            default_back_url: config.default_back_url.unwrap_or("/"),
         })
     }
 
+    /// Checks the serialized page size against the configured limits. If the page has not been
+    /// serialized yet, `page_size` is `None` and it is only serialized if any limit is set.
+    ///
+    /// # Errors
+    /// Returns an [`InertiaError::PageSizeError`] if the page is larger than the hard limit.
+    pub(crate) fn check_page_size(
+        &self,
+        page: &InertiaPage,
+        page_size: Option<usize>,
+    ) -> Result<(), InertiaError> {
+        if self.page_soft_size_limit.is_none() && self.page_hard_size_limit.is_none() {
+            return Ok(());
+        }
+
+        let page_size = match page_size {
+            Some(page_size) => page_size,
+            None => serialized_size(page)?,
+        };
+
+        if self
+            .page_hard_size_limit
+            .is_some_and(|hard_limit| page_size > hard_limit)
+        {
+            return Err(InertiaError::PageSizeError(format!(
+                "Page {} is {} bytes large, exceeding the hard limit of {} bytes. Largest props: {}.",
+                page.component.0,
+                page_size,
+                self.page_hard_size_limit.unwrap(),
+                largest_props(page)?
+            )));
+        }
+
+        if self
+            .page_soft_size_limit
+            .is_some_and(|soft_limit| page_size > soft_limit)
+        {
+            log::warn!(
+                "{}",
+                inertia_err_msg(format!(
+                    "Page {} is {} bytes large, exceeding the soft limit of {} bytes. Largest props: {}.",
+                    page.component.0,
+                    page_size,
+                    self.page_soft_size_limit.unwrap(),
+                    largest_props(page)?
+                ))
+            );
+        }
+
+        Ok(())
+    }
+
     pub fn get_view_data_mut(&mut self) -> &Map<String, Value> {
         &mut self.custom_view_data
     }
 
+    /// Removes the value cached by the cached props with the given key, so that it's resolved
+    /// again in the next request that includes them.
+    ///
+    /// # Arguments
+    /// * `key`     -   The key given to [`InertiaProp::cached`].
+    ///
+    /// [`InertiaProp::cached`]: crate::InertiaProp::cached
+    pub fn invalidate_cached_prop(&self, key: &str) {
+        self.props_cache.invalidate(key);
+    }
+
     /// Instantiates a [`NodeJsProc`] by calling [`NodeJsProc::start`] with the given path and the
     /// inertia `ssr_url` as server url.
     ///
@@ -1,12 +1,17 @@
+#![allow(clippy::doc_overindented_list_items)]
+
 mod config;
 mod error;
 mod features;
-mod http_method;
+mod flash;
 mod inertia;
 mod page;
+mod prop_keys;
 mod props;
+mod props_cache;
 mod providers;
 mod req_type;
+mod request_context;
 mod temporary_session;
 mod utils;
 
@@ -14,6 +19,8 @@ pub mod node_process;
 
 pub use config::{InertiaConfig, InertiaConfigBuilder};
 pub use error::InertiaError;
+pub use flash::FlashData;
+pub use flash::FlashStore;
 pub use inertia::Component;
 pub use inertia::Inertia;
 pub use inertia::InertiaService;
@@ -23,15 +30,41 @@ pub use inertia::TemplateResolverOutput;
 pub use inertia::ViewData;
 pub use page::InertiaPage;
 pub use page::InertiaSSRPage;
+pub use prop_keys::PropKeysCase;
 pub use props::InertiaProp;
 pub use props::InertiaProps;
+pub use props::IntoInertiaProps;
+pub use props::IntoPropValue;
+pub use props::OnceProp;
+pub use props::PropErrorPolicy;
+pub use props::PropResolver;
+pub use props::PropsMergeStrategy;
+pub use props::ScrollProp;
+pub use props_cache::InMemoryPropsCache;
+pub use props_cache::PropsCache;
+pub use request_context::InertiaRequestContext;
 pub use temporary_session::InertiaTemporarySession;
 
+#[cfg(feature = "macros")]
+pub use inertia_rust_macros::InertiaProps;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+    pub use serde_json;
+}
+
 #[cfg(feature = "actix")]
 pub mod actix {
-    pub use super::providers::actix::facade::{render, render_with_props};
+    pub use super::providers::actix::error_handlers::InertiaErrorHandlers;
+    pub use super::providers::actix::facade::{
+        back, clear_history, encrypt_history, invalidate_cached_prop, is_prefetch, location,
+        redirect_back, render, render_with_props, render_with_struct, response,
+    };
     pub use super::providers::actix::headers::InertiaHeader;
     pub use super::providers::actix::middleware::InertiaMiddleware;
+    pub use super::providers::actix::redirect::{ExternalRedirect, InertiaRedirect};
+    pub use super::providers::actix::response::InertiaResponseBuilder;
 }
 
 #[cfg(feature = "basic-vite-resolver")]
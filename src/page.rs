@@ -1,6 +1,8 @@
 use crate::inertia::Component;
+use crate::props::{MergeableProps, OnceProp, ScrollProp};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+use std::collections::HashMap;
 
 /// Inertia Full Page response to be rendered inside the root template
 /// on the first request or on a full visit request.
@@ -52,6 +54,66 @@ pub struct InertiaPage {
     pub(crate) url: String,
     /// Current assets version.
     pub(crate) version: Option<String>,
+    /// Deferred props' keys grouped by their group names. The client-side adapter
+    /// will request each group in a separate partial reload right after the page is rendered.
+    #[serde(
+        rename = "deferredProps",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub(crate) deferred_props: Option<HashMap<String, Vec<String>>>,
+    /// Keys of the props that must be appended to the ones the client-side already holds.
+    #[serde(rename = "mergeProps", skip_serializing_if = "Vec::is_empty", default)]
+    pub(crate) merge_props: Vec<String>,
+    /// Keys of the props that must be prepended to the ones the client-side already holds.
+    #[serde(
+        rename = "prependProps",
+        skip_serializing_if = "Vec::is_empty",
+        default
+    )]
+    pub(crate) prepend_props: Vec<String>,
+    /// Keys of the props that must be deeply merged into the ones the client-side already holds.
+    #[serde(
+        rename = "deepMergeProps",
+        skip_serializing_if = "Vec::is_empty",
+        default
+    )]
+    pub(crate) deep_merge_props: Vec<String>,
+    /// Paths (e.g. "users.data.id") of the keys the client-side must use to match merged items.
+    #[serde(
+        rename = "matchPropsOn",
+        skip_serializing_if = "Vec::is_empty",
+        default
+    )]
+    pub(crate) match_props_on: Vec<String>,
+    /// Metadata of the once props the client-side adapter must keep between visits.
+    #[serde(
+        rename = "onceProps",
+        skip_serializing_if = "HashMap::is_empty",
+        default
+    )]
+    pub(crate) once_props: HashMap<String, OnceProp>,
+    /// Pagination metadata of the scroll props loaded by the client-side infinite scrolling.
+    #[serde(
+        rename = "scrollProps",
+        skip_serializing_if = "HashMap::is_empty",
+        default
+    )]
+    pub(crate) scroll_props: HashMap<String, ScrollProp>,
+    /// Whether the client-side adapter must encrypt this page's history state.
+    #[serde(
+        rename = "encryptHistory",
+        skip_serializing_if = "std::ops::Not::not",
+        default
+    )]
+    pub(crate) encrypt_history: bool,
+    /// Whether the client-side adapter must clear its encrypted history states.
+    #[serde(
+        rename = "clearHistory",
+        skip_serializing_if = "std::ops::Not::not",
+        default
+    )]
+    pub(crate) clear_history: bool,
 }
 
 impl InertiaPage {
@@ -70,6 +132,42 @@ impl InertiaPage {
     pub fn get_version(&self) -> &Option<String> {
         &self.version
     }
+
+    pub fn get_deferred_props(&self) -> &Option<HashMap<String, Vec<String>>> {
+        &self.deferred_props
+    }
+
+    pub fn get_merge_props(&self) -> &Vec<String> {
+        &self.merge_props
+    }
+
+    pub fn get_prepend_props(&self) -> &Vec<String> {
+        &self.prepend_props
+    }
+
+    pub fn get_deep_merge_props(&self) -> &Vec<String> {
+        &self.deep_merge_props
+    }
+
+    pub fn get_match_props_on(&self) -> &Vec<String> {
+        &self.match_props_on
+    }
+
+    pub fn get_once_props(&self) -> &HashMap<String, OnceProp> {
+        &self.once_props
+    }
+
+    pub fn get_scroll_props(&self) -> &HashMap<String, ScrollProp> {
+        &self.scroll_props
+    }
+
+    pub fn get_encrypt_history(&self) -> bool {
+        self.encrypt_history
+    }
+
+    pub fn get_clear_history(&self) -> bool {
+        self.clear_history
+    }
 }
 
 impl InertiaPage {
@@ -102,20 +200,71 @@ impl InertiaPage {
             url,
             props,
             version,
+            deferred_props: None,
+            merge_props: Vec::new(),
+            prepend_props: Vec::new(),
+            deep_merge_props: Vec::new(),
+            match_props_on: Vec::new(),
+            once_props: HashMap::new(),
+            scroll_props: HashMap::new(),
+            encrypt_history: false,
+            clear_history: false,
         }
     }
+
+    /// Sets the page's deferred props groups. Empty groups maps are ignored, so that
+    /// the `deferredProps` field is omitted from the page object.
+    pub(crate) fn with_deferred_props(
+        mut self,
+        deferred_props: HashMap<String, Vec<String>>,
+    ) -> Self {
+        if !deferred_props.is_empty() {
+            self.deferred_props = Some(deferred_props);
+        }
+
+        self
+    }
+
+    /// Sets the keys of the props the client-side adapter must merge with its current ones.
+    pub(crate) fn with_mergeable_props(mut self, mergeable_props: MergeableProps) -> Self {
+        self.merge_props = mergeable_props.merge;
+        self.prepend_props = mergeable_props.prepend;
+        self.deep_merge_props = mergeable_props.deep_merge;
+        self.match_props_on = mergeable_props.match_on;
+        self
+    }
+
+    /// Sets the metadata of the once props the client-side adapter must keep.
+    pub(crate) fn with_once_props(mut self, once_props: HashMap<String, OnceProp>) -> Self {
+        self.once_props = once_props;
+        self
+    }
+
+    /// Sets the pagination metadata of the scroll props included in the page.
+    pub(crate) fn with_scroll_props(mut self, scroll_props: HashMap<String, ScrollProp>) -> Self {
+        self.scroll_props = scroll_props;
+        self
+    }
+
+    /// Sets whether the client-side adapter must encrypt the page's history state and
+    /// whether it must clear the history states encrypted so far.
+    pub(crate) fn with_history(mut self, encrypt_history: bool, clear_history: bool) -> Self {
+        self.encrypt_history = encrypt_history;
+        self.clear_history = clear_history;
+        self
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::props::InertiaProp;
-    use crate::req_type::{InertiaRequestType, PartialComponent};
-    use crate::{Component, InertiaPage};
+    use crate::props::{InertiaProp, PropErrorPolicy, PropResolver, ScrollProp};
+    use crate::req_type::{InertiaRequestType, PartialComponent, ScrollMergeIntent};
+    use crate::request_context::InertiaRequestContext;
+    use crate::{Component, InertiaError, InertiaPage};
     use actix_web::test;
     use serde::Serialize;
     use serde_json::json;
     use std::collections::HashMap;
-    use std::sync::Arc;
 
     #[test]
     async fn test_inertia_partials_visit_page() {
@@ -155,13 +304,19 @@ mod test {
             component: Component("Events".to_string()),
             only: Vec::from(["events".to_string()]),
             except: Vec::new(),
+            scroll_merge_intent: ScrollMergeIntent::Append,
         });
 
         let page = InertiaPage::new(
             Component("Events".into()),
             "/events/80".to_string(),
             Some("generated_version".into()),
-            InertiaProp::resolve_props(&props, req_type),
+            InertiaProp::resolve_props(
+                &props,
+                &InertiaRequestContext::from_request_type(req_type, vec![]),
+            )
+            .await
+            .unwrap(),
         );
 
         let json_page_example = json!({
@@ -186,7 +341,7 @@ mod test {
         let mut props = HashMap::<String, InertiaProp>::new();
         props.insert(
             "radioStatus".into(),
-            InertiaProp::Demand(Arc::new(|| json!({"announcer": "John Doe"}))),
+            InertiaProp::Demand(PropResolver::new(|| json!({"announcer": "John Doe"}))),
         );
         props.insert(
             "categories".into(),
@@ -202,7 +357,12 @@ mod test {
             Component("Categories".into()),
             "/categories".to_string(),
             Some("generated_version".into()),
-            InertiaProp::resolve_props(&props, req_type),
+            InertiaProp::resolve_props(
+                &props,
+                &InertiaRequestContext::from_request_type(req_type, vec![]),
+            )
+            .await
+            .unwrap(),
         );
 
         let json_page_example = json!({
@@ -220,4 +380,463 @@ mod test {
             serde_json::to_string(&json_page_example).unwrap(),
         );
     }
+
+    #[test]
+    async fn test_inertia_deferred_props_page() {
+        let mut props = HashMap::<String, InertiaProp>::new();
+        props.insert(
+            "permissions".into(),
+            InertiaProp::Deferred(PropResolver::new(|| json!(["users.create"])), None),
+        );
+        props.insert(
+            "teams".into(),
+            InertiaProp::Deferred(PropResolver::new(|| json!([])), Some("attributes".into())),
+        );
+        props.insert(
+            "users".into(),
+            InertiaProp::Deferred(PropResolver::new(|| json!([])), Some("attributes".into())),
+        );
+        props.insert("title".into(), InertiaProp::Data("Dashboard".into()));
+
+        // Request headers
+        // X-Inertia: true
+        // X-Inertia-Version: generated_version
+        let req_type = InertiaRequestType::Standard;
+
+        let page = InertiaPage::new(
+            Component("Dashboard".into()),
+            "/dashboard".to_string(),
+            Some("generated_version".into()),
+            InertiaProp::resolve_props(
+                &props,
+                &InertiaRequestContext::from_request_type(req_type.clone(), vec![]),
+            )
+            .await
+            .unwrap(),
+        )
+        .with_deferred_props(InertiaProp::resolve_deferred_props(&props, &req_type));
+
+        assert_eq!(
+            json!(page),
+            json!({
+              "component": "Dashboard",
+              "props": { "title": "Dashboard" },
+              "url": "/dashboard",
+              "version": "generated_version",
+              "deferredProps": {
+                "default": ["permissions"],
+                "attributes": ["teams", "users"]
+              }
+            })
+        );
+
+        // Request headers
+        // X-Inertia: true
+        // X-Inertia-Version: generated_version
+        // X-Inertia-Partial-Data: permissions
+        // X-Inertia-Partial-Component: Dashboard
+        let req_type = InertiaRequestType::Partial(PartialComponent {
+            component: Component("Dashboard".to_string()),
+            only: Vec::from(["permissions".to_string()]),
+            except: Vec::new(),
+            scroll_merge_intent: ScrollMergeIntent::Append,
+        });
+
+        let page = InertiaPage::new(
+            Component("Dashboard".into()),
+            "/dashboard".to_string(),
+            Some("generated_version".into()),
+            InertiaProp::resolve_props(
+                &props,
+                &InertiaRequestContext::from_request_type(req_type.clone(), vec![]),
+            )
+            .await
+            .unwrap(),
+        )
+        .with_deferred_props(InertiaProp::resolve_deferred_props(&props, &req_type));
+
+        assert_eq!(
+            json!(page),
+            json!({
+              "component": "Dashboard",
+              "props": { "permissions": ["users.create"] },
+              "url": "/dashboard",
+              "version": "generated_version"
+            })
+        );
+    }
+
+    #[test]
+    async fn test_inertia_mergeable_props_page() {
+        let mut props = HashMap::<String, InertiaProp>::new();
+        props.insert("posts".into(), InertiaProp::Merge(json!([{"id": 3}])));
+        props.insert("messages".into(), InertiaProp::Prepend(json!([{"id": 9}])));
+        props.insert("notifications".into(), InertiaProp::Merge(json!([])));
+
+        // Request headers
+        // X-Inertia: true
+        // X-Inertia-Version: generated_version
+        // X-Inertia-Partial-Data: posts,messages
+        // X-Inertia-Partial-Component: Feed
+        // X-Inertia-Reset: messages
+        let req_type = InertiaRequestType::Partial(PartialComponent {
+            component: Component("Feed".to_string()),
+            only: Vec::from(["posts".to_string(), "messages".to_string()]),
+            except: Vec::new(),
+            scroll_merge_intent: ScrollMergeIntent::Append,
+        });
+
+        let page = InertiaPage::new(
+            Component("Feed".into()),
+            "/feed?page=2".to_string(),
+            Some("generated_version".into()),
+            InertiaProp::resolve_props(
+                &props,
+                &InertiaRequestContext::from_request_type(req_type.clone(), vec![]),
+            )
+            .await
+            .unwrap(),
+        )
+        .with_mergeable_props(InertiaProp::resolve_mergeable_props(
+            &props,
+            &req_type,
+            &["messages".to_string()],
+        ));
+
+        assert_eq!(
+            json!(page),
+            json!({
+              "component": "Feed",
+              "props": {
+                "posts": [{"id": 3}],
+                "messages": [{"id": 9}]
+              },
+              "url": "/feed?page=2",
+              "version": "generated_version",
+              // "notifications" is not included, and "messages" has been reset
+              "mergeProps": ["posts"]
+            })
+        );
+    }
+
+    #[test]
+    async fn test_inertia_scroll_props_page() {
+        let mut props = HashMap::<String, InertiaProp>::new();
+        props.insert(
+            "posts".into(),
+            InertiaProp::Scroll(
+                PropResolver::new(|| json!({"data": [{"id": 1}, {"id": 2}]})),
+                ScrollProp::new("page", 2)
+                    .with_previous_page(1)
+                    .with_next_page(3),
+            ),
+        );
+
+        // Request headers
+        // X-Inertia: true
+        // X-Inertia-Version: generated_version
+        // X-Inertia-Partial-Data: posts
+        // X-Inertia-Partial-Component: Feed
+        // X-Inertia-Infinite-Scroll-Merge-Intent: prepend
+        let req_type = InertiaRequestType::Partial(PartialComponent {
+            component: Component("Feed".to_string()),
+            only: Vec::from(["posts".to_string()]),
+            except: Vec::new(),
+            scroll_merge_intent: ScrollMergeIntent::Prepend,
+        });
+
+        let page = InertiaPage::new(
+            Component("Feed".into()),
+            "/feed?page=1".to_string(),
+            Some("generated_version".into()),
+            InertiaProp::resolve_props(
+                &props,
+                &InertiaRequestContext::from_request_type(req_type.clone(), vec![]),
+            )
+            .await
+            .unwrap(),
+        )
+        .with_mergeable_props(InertiaProp::resolve_mergeable_props(&props, &req_type, &[]))
+        .with_scroll_props(InertiaProp::resolve_scroll_props(&props, &req_type, &[]));
+
+        assert_eq!(
+            json!(page),
+            json!({
+              "component": "Feed",
+              "props": {
+                "posts": {"data": [{"id": 1}, {"id": 2}]}
+              },
+              "url": "/feed?page=1",
+              "version": "generated_version",
+              "prependProps": ["posts.data"],
+              "scrollProps": {
+                "posts": {
+                  "pageName": "page",
+                  "previousPage": 1,
+                  "nextPage": 3,
+                  "currentPage": 2,
+                  "reset": false
+                }
+              }
+            })
+        );
+    }
+
+    #[test]
+    async fn test_inertia_deep_merge_props_page() {
+        let mut props = HashMap::<String, InertiaProp>::new();
+        props.insert(
+            "users".into(),
+            InertiaProp::DeepMerge(
+                json!({"data": [{"id": 11}], "meta": {"page": 2}}),
+                vec!["data.id".into()],
+            ),
+        );
+
+        // Request headers
+        // X-Inertia: true
+        // X-Inertia-Version: generated_version
+        let req_type = InertiaRequestType::Standard;
+
+        let page = InertiaPage::new(
+            Component("Users".into()),
+            "/users?page=2".to_string(),
+            Some("generated_version".into()),
+            InertiaProp::resolve_props(
+                &props,
+                &InertiaRequestContext::from_request_type(req_type.clone(), vec![]),
+            )
+            .await
+            .unwrap(),
+        )
+        .with_mergeable_props(InertiaProp::resolve_mergeable_props(&props, &req_type, &[]));
+
+        assert_eq!(
+            json!(page),
+            json!({
+              "component": "Users",
+              "props": {
+                "users": {"data": [{"id": 11}], "meta": {"page": 2}}
+              },
+              "url": "/users?page=2",
+              "version": "generated_version",
+              "deepMergeProps": ["users"],
+              "matchPropsOn": ["users.data.id"]
+            })
+        );
+    }
+
+    #[test]
+    async fn test_inertia_once_props_page() {
+        let mut props = HashMap::<String, InertiaProp>::new();
+        props.insert(
+            "countries".into(),
+            InertiaProp::Once(PropResolver::new(|| json!(["Brazil", "Portugal"]))),
+        );
+        props.insert("title".into(), InertiaProp::Data("Sign up".into()));
+
+        // Request headers
+        // X-Inertia: true
+        // X-Inertia-Version: generated_version
+        // X-Inertia-Except-Once-Props: countries
+        let req_type = InertiaRequestType::Standard;
+        let except_once_props = vec!["countries".to_string()];
+
+        let page = InertiaPage::new(
+            Component("SignUp".into()),
+            "/signup".to_string(),
+            Some("generated_version".into()),
+            InertiaProp::resolve_props(
+                &props,
+                &InertiaRequestContext::from_request_type(req_type.clone(), except_once_props),
+            )
+            .await
+            .unwrap(),
+        )
+        .with_once_props(InertiaProp::resolve_once_props(&props, &req_type));
+
+        assert_eq!(
+            json!(page),
+            json!({
+              "component": "SignUp",
+              "props": {
+                // "countries": ["Brazil", "Portugal"],     // NOT included, client holds it
+                "title": "Sign up"
+              },
+              "url": "/signup",
+              "version": "generated_version",
+              "onceProps": {
+                "countries": { "prop": "countries", "expiresAt": null }
+              }
+            })
+        );
+
+        // Request headers
+        // X-Inertia: true
+        // X-Inertia-Version: generated_version
+        let page = InertiaPage::new(
+            Component("SignUp".into()),
+            "/signup".to_string(),
+            Some("generated_version".into()),
+            InertiaProp::resolve_props(
+                &props,
+                &InertiaRequestContext::from_request_type(req_type, vec![]),
+            )
+            .await
+            .unwrap(),
+        );
+
+        assert_eq!(
+            page.get_props().get("countries"),
+            Some(&json!(["Brazil", "Portugal"]))
+        );
+    }
+
+    #[test]
+    async fn test_inertia_async_props_page() {
+        use futures::channel::oneshot;
+        use std::sync::{Arc, Mutex};
+
+        // "stats" can only finish after "users" has started, so that this test would never end
+        // if the resolvers were awaited one after another.
+        let (sender, receiver) = oneshot::channel::<()>();
+        let sender = Arc::new(Mutex::new(Some(sender)));
+        let receiver = Arc::new(Mutex::new(Some(receiver)));
+
+        let mut props = HashMap::<String, InertiaProp>::new();
+        props.insert(
+            "stats".into(),
+            InertiaProp::Lazy(PropResolver::new_async(move || {
+                let receiver = receiver.lock().unwrap().take().unwrap();
+                async move {
+                    receiver.await.unwrap();
+                    json!({"visits": 10})
+                }
+            })),
+        );
+        props.insert(
+            "users".into(),
+            InertiaProp::Demand(PropResolver::new_async(move || {
+                let sender = sender.lock().unwrap().take().unwrap();
+                async move {
+                    sender.send(()).unwrap();
+                    json!(["John Doe"])
+                }
+            })),
+        );
+
+        // Request headers
+        // X-Inertia: true
+        // X-Inertia-Version: generated_version
+        // X-Inertia-Partial-Data: stats,users
+        // X-Inertia-Partial-Component: Dashboard
+        let req_type = InertiaRequestType::Partial(PartialComponent {
+            component: Component("Dashboard".to_string()),
+            only: Vec::from(["stats".to_string(), "users".to_string()]),
+            except: Vec::new(),
+            scroll_merge_intent: ScrollMergeIntent::Append,
+        });
+
+        let props = InertiaProp::resolve_props(
+            &props,
+            &InertiaRequestContext::from_request_type(req_type, vec![]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            json!(props),
+            json!({ "stats": {"visits": 10}, "users": ["John Doe"] })
+        );
+    }
+
+    #[test]
+    async fn test_inertia_fallible_props() {
+        let mut props = HashMap::<String, InertiaProp>::new();
+        props.insert(
+            "events".into(),
+            InertiaProp::Lazy(
+                PropResolver::new_fallible(|| Err("connection refused"))
+                    .on_error(PropErrorPolicy::Fallback(json!([]))),
+            ),
+        );
+
+        // Request headers
+        // X-Inertia: true
+        // X-Inertia-Version: generated_version
+        let req_type = InertiaRequestType::Standard;
+
+        let resolved_props = InertiaProp::resolve_props(
+            &props,
+            &InertiaRequestContext::from_request_type(req_type.clone(), vec![]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(json!(resolved_props), json!({ "events": [] }));
+
+        props.insert(
+            "users".into(),
+            InertiaProp::Lazy(PropResolver::new_fallible_async(|| async {
+                Err("connection refused")
+            })),
+        );
+
+        let err = InertiaProp::resolve_props(
+            &props,
+            &InertiaRequestContext::from_request_type(req_type, vec![]),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(
+            err,
+            InertiaError::PropError(key, cause) if key == "users" && cause == "connection refused"
+        ));
+    }
+
+    #[test]
+    async fn test_inertia_nested_partials_visit_page() {
+        let mut props = HashMap::<String, InertiaProp>::new();
+        props.insert(
+            "user".into(),
+            InertiaProp::Data(json!({"name": "John Doe", "permissions": ["users.create"]})),
+        );
+        props.insert(
+            "stats".into(),
+            InertiaProp::Lazy(PropResolver::new(
+                || json!({"daily": {"visits": 10, "sales": 2}, "monthly": {"visits": 300}}),
+            )),
+        );
+        props.insert("title".into(), InertiaProp::Data("Dashboard".into()));
+
+        // Request headers
+        // X-Inertia: true
+        // X-Inertia-Version: generated_version
+        // X-Inertia-Partial-Data: user.permissions,stats.daily
+        // X-Inertia-Partial-Except: stats.daily.sales
+        // X-Inertia-Partial-Component: Dashboard
+        let req_type = InertiaRequestType::Partial(PartialComponent {
+            component: Component("Dashboard".to_string()),
+            only: Vec::from(["user.permissions".to_string(), "stats.daily".to_string()]),
+            except: Vec::from(["stats.daily.sales".to_string()]),
+            scroll_merge_intent: ScrollMergeIntent::Append,
+        });
+
+        let props = InertiaProp::resolve_props(
+            &props,
+            &InertiaRequestContext::from_request_type(req_type, vec![]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            json!(props),
+            json!({
+              "user": { "permissions": ["users.create"] },
+              "stats": { "daily": { "visits": 10 } }
+              // "title": "Dashboard"                       // NOT included
+            })
+        );
+    }
 }
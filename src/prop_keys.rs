@@ -0,0 +1,290 @@
+use crate::props::{InertiaProp, InertiaProps, PropResolver};
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// The case prop keys are converted to when `Inertia` builds a page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PropKeysCase {
+    /// `user_name` becomes `userName`.
+    CamelCase,
+    /// `userName` becomes `user_name`.
+    SnakeCase,
+}
+
+impl PropKeysCase {
+    pub(crate) fn convert(&self, key: &str) -> String {
+        match self {
+            PropKeysCase::CamelCase => to_camel_case(key),
+            PropKeysCase::SnakeCase => to_snake_case(key),
+        }
+    }
+}
+
+/// Renames the props' keys, and optionally the keys of the objects nested in their values,
+/// before they are resolved. Since the props are renamed before the partial reloads filter
+/// them, the keys requested by the client-side adapter match the renamed ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct PropKeysTransform {
+    pub case: PropKeysCase,
+    pub recursive: bool,
+}
+
+impl PropKeysTransform {
+    pub fn transform_props(&self, props: InertiaProps) -> InertiaProps {
+        props
+            .into_iter()
+            .map(|(key, prop)| (self.case.convert(&key), self.transform_prop(prop)))
+            .collect()
+    }
+
+    /// Transforms props that are shared between renders, such as the shared props. The props
+    /// are only cloned if their values must be transformed too.
+    pub fn transform_borrowed_props<'a>(
+        &self,
+        props: &'a InertiaProps,
+    ) -> HashMap<String, Cow<'a, InertiaProp>> {
+        props
+            .iter()
+            .map(|(key, prop)| {
+                let prop = match self.recursive {
+                    true => Cow::Owned(self.transform_prop(prop.clone())),
+                    false => Cow::Borrowed(prop),
+                };
+
+                (self.case.convert(key), prop)
+            })
+            .collect()
+    }
+
+    fn transform_prop(&self, prop: InertiaProp) -> InertiaProp {
+        if !self.recursive {
+            return prop;
+        }
+
+        match prop {
+            InertiaProp::Data(value) => InertiaProp::Data(self.transform_value(value)),
+            InertiaProp::Always(value) => InertiaProp::Always(self.transform_value(value)),
+            InertiaProp::Merge(value) => InertiaProp::Merge(self.transform_value(value)),
+            InertiaProp::Prepend(value) => InertiaProp::Prepend(self.transform_value(value)),
+            InertiaProp::DeepMerge(value, match_on) => InertiaProp::DeepMerge(
+                self.transform_value(value),
+                match_on
+                    .iter()
+                    .map(|path| self.transform_path(path))
+                    .collect(),
+            ),
+            InertiaProp::Lazy(resolver) => InertiaProp::Lazy(self.transform_resolver(resolver)),
+            InertiaProp::Demand(resolver) => InertiaProp::Demand(self.transform_resolver(resolver)),
+            InertiaProp::Deferred(resolver, group) => {
+                InertiaProp::Deferred(self.transform_resolver(resolver), group)
+            }
+            InertiaProp::Once(resolver) => InertiaProp::Once(self.transform_resolver(resolver)),
+            InertiaProp::Scroll(resolver, metadata) => {
+                InertiaProp::Scroll(self.transform_resolver(resolver), metadata)
+            }
+        }
+    }
+
+    fn transform_resolver(&self, resolver: PropResolver) -> PropResolver {
+        let transform = *self;
+        resolver.map(move |value| transform.transform_value(value))
+    }
+
+    fn transform_path(&self, path: &str) -> String {
+        path.split('.')
+            .map(|segment| self.case.convert(segment))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    fn transform_value(&self, value: Value) -> Value {
+        match value {
+            Value::Object(object) => Value::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (self.case.convert(&key), self.transform_value(value)))
+                    .collect::<Map<String, Value>>(),
+            ),
+            Value::Array(items) => Value::Array(
+                items
+                    .into_iter()
+                    .map(|item| self.transform_value(item))
+                    .collect(),
+            ),
+            value => value,
+        }
+    }
+}
+
+fn to_camel_case(key: &str) -> String {
+    let mut camel_case = String::with_capacity(key.len());
+    let mut uppercase_next = false;
+
+    for char in key.chars() {
+        // leading underscores are kept, as they usually mean something to the client-side
+        if char == '_' && !camel_case.trim_start_matches('_').is_empty() {
+            uppercase_next = true;
+            continue;
+        }
+
+        if uppercase_next {
+            camel_case.extend(char.to_uppercase());
+            uppercase_next = false;
+        } else {
+            camel_case.push(char);
+        }
+    }
+
+    camel_case
+}
+
+fn to_snake_case(key: &str) -> String {
+    let chars = key.chars().collect::<Vec<_>>();
+    let mut snake_case = String::with_capacity(key.len() + 4);
+
+    for (i, char) in chars.iter().enumerate() {
+        if char.is_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_is_lowercase = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+
+            // "userId" => "user_id", and "HTMLParser" => "html_parser"
+            if previous != '_'
+                && (previous.is_lowercase()
+                    || previous.is_ascii_digit()
+                    || (previous.is_uppercase() && next_is_lowercase))
+            {
+                snake_case.push('_');
+            }
+        }
+
+        snake_case.extend(char.to_lowercase());
+    }
+
+    snake_case
+}
+
+#[cfg(test)]
+mod test {
+    use super::{PropKeysCase, PropKeysTransform};
+    use crate::props::{InertiaProp, PropResolver};
+    use crate::req_type::{InertiaRequestType, PartialComponent, ScrollMergeIntent};
+    use crate::request_context::InertiaRequestContext;
+    use crate::Component;
+    use serde_json::json;
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_prop_keys_case_conversion() {
+        let camel_case = PropKeysCase::CamelCase;
+        assert_eq!("userName", camel_case.convert("user_name"));
+        assert_eq!("userName", camel_case.convert("userName"));
+        assert_eq!("_tokenId", camel_case.convert("_token_id"));
+
+        let snake_case = PropKeysCase::SnakeCase;
+        assert_eq!("user_name", snake_case.convert("userName"));
+        assert_eq!("user_name", snake_case.convert("user_name"));
+        assert_eq!("html_parser", snake_case.convert("HTMLParser"));
+        assert_eq!("user_id", snake_case.convert("userID"));
+    }
+
+    #[tokio::test]
+    async fn test_transform_props() {
+        let mut props = HashMap::new();
+        props.insert(
+            "current_user".to_string(),
+            InertiaProp::Data(json!({"first_name": "John", "pets": [{"pet_name": "Rex"}]})),
+        );
+        props.insert(
+            "daily_stats".to_string(),
+            InertiaProp::Lazy(PropResolver::new(|| json!({"page_views": 42}))),
+        );
+
+        let transform = PropKeysTransform {
+            case: PropKeysCase::CamelCase,
+            recursive: false,
+        };
+
+        let resolved = InertiaProp::resolve_props(
+            &transform.transform_props(props.clone()),
+            &InertiaRequestContext::from_request_type(InertiaRequestType::Standard, vec![]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            json!({
+                "currentUser": {"first_name": "John", "pets": [{"pet_name": "Rex"}]},
+                "dailyStats": {"page_views": 42}
+            }),
+            json!(resolved)
+        );
+
+        let transform = PropKeysTransform {
+            case: PropKeysCase::CamelCase,
+            recursive: true,
+        };
+
+        let resolved = InertiaProp::resolve_props(
+            &transform.transform_props(props.clone()),
+            &InertiaRequestContext::from_request_type(InertiaRequestType::Standard, vec![]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            json!({
+                "currentUser": {"firstName": "John", "pets": [{"petName": "Rex"}]},
+                "dailyStats": {"pageViews": 42}
+            }),
+            json!(resolved)
+        );
+
+        // X-Inertia-Partial-Data: dailyStats
+        let req_type = InertiaRequestType::Partial(PartialComponent {
+            component: Component("Dashboard".into()),
+            only: vec!["dailyStats".into()],
+            except: vec![],
+            scroll_merge_intent: ScrollMergeIntent::Append,
+        });
+
+        let resolved = InertiaProp::resolve_props(
+            &transform.transform_props(props.clone()),
+            &InertiaRequestContext::from_request_type(req_type, vec![]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(json!({"dailyStats": {"pageViews": 42}}), json!(resolved));
+    }
+
+    #[test]
+    fn test_transform_borrowed_props() {
+        let mut props = HashMap::new();
+        props.insert(
+            "current_user".to_string(),
+            InertiaProp::Data(json!({"first_name": "John"})),
+        );
+
+        let transform = PropKeysTransform {
+            case: PropKeysCase::CamelCase,
+            recursive: false,
+        };
+
+        // values that aren't transformed aren't cloned either
+        let transformed = transform.transform_borrowed_props(&props);
+        assert!(matches!(transformed["currentUser"], Cow::Borrowed(_)));
+
+        let transform = PropKeysTransform {
+            case: PropKeysCase::CamelCase,
+            recursive: true,
+        };
+
+        let transformed = transform.transform_borrowed_props(&props);
+        let Cow::Owned(InertiaProp::Data(value)) = &transformed["currentUser"] else {
+            panic!("the transformed prop should be owned data");
+        };
+        assert_eq!(&json!({"firstName": "John"}), value);
+    }
+}
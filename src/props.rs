@@ -1,8 +1,251 @@
-use crate::req_type::{InertiaRequestType, PartialComponent};
+use crate::error::InertiaError;
+use crate::req_type::{InertiaRequestType, PartialComponent, ScrollMergeIntent};
+use crate::request_context::InertiaRequestContext;
+use crate::utils::inertia_err_msg;
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+use std::borrow::Borrow;
+use std::convert::Infallible;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
 use std::{collections::HashMap, sync::Arc};
 
+/// The future returned by a [`PropResolver`]'s inner function. It doesn't need to be `Send`,
+/// since props are resolved in the same thread that handles the request.
+pub(crate) type PropResolverOutput = Pin<Box<dyn Future<Output = Result<Value, String>>>>;
+
+/// A value returned by a request-aware [`PropResolver`] function. It's implemented for
+/// [`Value`] and for `Result<Value, E>`, so that request-aware resolvers may also fail.
+pub trait IntoPropValue {
+    fn into_prop_value(self) -> Result<Value, String>;
+}
+
+impl IntoPropValue for Value {
+    fn into_prop_value(self) -> Result<Value, String> {
+        Ok(self)
+    }
+}
+
+impl<E> IntoPropValue for Result<Value, E>
+where
+    E: fmt::Display,
+{
+    fn into_prop_value(self) -> Result<Value, String> {
+        self.map_err(|err| err.to_string())
+    }
+}
+
+/// What to do when a fallible [`PropResolver`] fails.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum PropErrorPolicy {
+    /// Fails the whole render with an [`InertiaError::PropError`] naming the prop.
+    #[default]
+    Fail,
+    /// Logs a warning and sends the given value in place of the prop.
+    Fallback(Value),
+}
+
+/// A function that evaluates a prop only when it's going to be sent to the client.
+///
+/// Synchronous functions are created with [`PropResolver::new`], while asynchronous ones (e.g.,
+/// a database query) are created with [`PropResolver::new_async`]. Every asynchronous resolver
+/// included in a response is awaited concurrently with the others.
+///
+/// Functions that may fail are created with [`PropResolver::new_fallible`] or
+/// [`PropResolver::new_fallible_async`]. What happens when they fail is defined by the
+/// resolver's [`PropErrorPolicy`].
+///
+/// Functions that need to read the request (e.g., to get the authenticated user from the
+/// request extensions) are created with [`PropResolver::new_with_request`] or
+/// [`PropResolver::new_with_request_async`]. They receive an [`InertiaRequestContext`] when
+/// the prop is resolved.
+///
+/// # Examples
+/// ```rust
+/// use inertia_rust::{InertiaProp, PropErrorPolicy, PropResolver};
+/// use serde_json::json;
+///
+/// let lazy_prop = InertiaProp::Lazy(PropResolver::new(|| json!({"foo": "bar"})));
+///
+/// let async_prop = InertiaProp::Demand(PropResolver::new_async(|| async move {
+///     // let users = database.fetch_users().await;
+///     json!(["John Doe"])
+/// }));
+///
+/// let fallible_prop = InertiaProp::Lazy(
+///     PropResolver::new_fallible(|| serde_json::to_value(vec!["foo", "bar"]))
+///         .on_error(PropErrorPolicy::Fallback(json!([]))),
+/// );
+///
+/// let request_aware_prop = InertiaProp::Lazy(PropResolver::new_with_request(|ctx| {
+///     json!({ "isPartialReload": ctx.is_partial_reload() })
+/// }));
+/// ```
+#[derive(Clone)]
+pub struct PropResolver {
+    resolver: Arc<dyn Fn(InertiaRequestContext) -> PropResolverOutput + Send + Sync>,
+    on_error: PropErrorPolicy,
+}
+
+impl PropResolver {
+    /// Creates a resolver from a synchronous function.
+    pub fn new<F>(resolver: F) -> Self
+    where
+        F: Fn() -> Value + Send + Sync + 'static,
+    {
+        Self::new_fallible(move || Ok::<_, Infallible>(resolver()))
+    }
+
+    /// Creates a resolver from a function that returns a future.
+    pub fn new_async<F, Fut>(resolver: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Value> + 'static,
+    {
+        Self::new_fallible_async(move || {
+            let future = resolver();
+            async move { Ok::<_, Infallible>(future.await) }
+        })
+    }
+
+    /// Creates a resolver from a synchronous function that may fail.
+    pub fn new_fallible<F, E>(resolver: F) -> Self
+    where
+        F: Fn() -> Result<Value, E> + Send + Sync + 'static,
+        E: fmt::Display,
+    {
+        Self::new_with_request(move |_ctx| resolver())
+    }
+
+    /// Creates a resolver from a function that returns a future that may fail.
+    pub fn new_fallible_async<F, Fut, E>(resolver: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, E>> + 'static,
+        E: fmt::Display,
+    {
+        Self::new_with_request_async(move |_ctx| resolver())
+    }
+
+    /// Creates a resolver from a synchronous function that reads the request being rendered.
+    pub fn new_with_request<F, R>(resolver: F) -> Self
+    where
+        F: Fn(&InertiaRequestContext) -> R + Send + Sync + 'static,
+        R: IntoPropValue,
+    {
+        Self {
+            resolver: Arc::new(move |ctx| {
+                let value = resolver(&ctx).into_prop_value();
+                Box::pin(async move { value })
+            }),
+            on_error: PropErrorPolicy::default(),
+        }
+    }
+
+    /// Creates a resolver from a function that reads the request being rendered and
+    /// returns a future.
+    pub fn new_with_request_async<F, Fut, R>(resolver: F) -> Self
+    where
+        F: Fn(InertiaRequestContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = R> + 'static,
+        R: IntoPropValue,
+    {
+        Self {
+            resolver: Arc::new(move |ctx| {
+                let future = resolver(ctx);
+                Box::pin(async move { future.await.into_prop_value() })
+            }),
+            on_error: PropErrorPolicy::default(),
+        }
+    }
+
+    /// Caches the values resolved by this resolver under `key` for `ttl`, in the props cache
+    /// held by `Inertia`. While the value is cached, the resolver isn't called. Failures
+    /// aren't cached.
+    ///
+    /// The cache is shared by every request, so the same value is served to every user. Don't
+    /// cache request-aware resolvers (e.g., a user's permissions) unless `key` identifies the
+    /// user, as one user's value would leak to the others.
+    pub fn cached(self, key: impl ToString, ttl: Duration) -> Self {
+        let key = key.to_string();
+        let resolver = self.resolver;
+
+        Self {
+            resolver: Arc::new(move |ctx| {
+                if let Some(value) = ctx.props_cache().get(&key) {
+                    return Box::pin(async move { Ok(value) });
+                }
+
+                let key = key.clone();
+                let future = resolver(ctx.clone());
+
+                Box::pin(async move {
+                    let value = future.await?;
+                    ctx.props_cache().set(&key, value.clone(), ttl);
+                    Ok(value)
+                })
+            }),
+            on_error: self.on_error,
+        }
+    }
+
+    /// Transforms the values resolved by this resolver with `f`.
+    pub(crate) fn map<F>(self, f: F) -> Self
+    where
+        F: Fn(Value) -> Value + Send + Sync + 'static,
+    {
+        let resolver = self.resolver;
+        let f = Arc::new(f);
+
+        Self {
+            resolver: Arc::new(move |ctx| {
+                let future = resolver(ctx);
+                let f = f.clone();
+                Box::pin(async move { future.await.map(|value| f(value)) })
+            }),
+            on_error: self.on_error,
+        }
+    }
+
+    /// Sets what to do if the resolver fails. Defaults to [`PropErrorPolicy::Fail`].
+    pub fn on_error(mut self, policy: PropErrorPolicy) -> Self {
+        self.on_error = policy;
+        self
+    }
+
+    #[inline]
+    pub(crate) async fn resolve(
+        &self,
+        key: &str,
+        ctx: &InertiaRequestContext,
+    ) -> Result<Value, InertiaError> {
+        let err = match (self.resolver)(ctx.clone()).await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+
+        match &self.on_error {
+            PropErrorPolicy::Fail => Err(InertiaError::PropError(key.to_string(), err)),
+            PropErrorPolicy::Fallback(value) => {
+                log::warn!(
+                    "{}",
+                    inertia_err_msg(format!(
+                        "Failed to resolve prop \"{}\", sending its fallback value instead. {}",
+                        key, err
+                    ))
+                );
+
+                Ok(value.clone())
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
+#[non_exhaustive]
 pub enum InertiaProp {
     /// - ALWAYS included on standard visits
     /// - OPTIONALLY included on partial reloads
@@ -11,7 +254,7 @@ pub enum InertiaProp {
     /// - ALWAYS included on standard visits
     /// - OPTIONALLY included on partial reloads
     /// - ONLY evaluated when included
-    Lazy(Arc<dyn Fn() -> Value + Send + Sync>),
+    Lazy(PropResolver),
     /// - ALWAYS included on standard visits
     /// - ALWAYS included on partial reloads (even if not requested or excepted)
     /// - ALWAYS evaluated
@@ -19,71 +262,782 @@ pub enum InertiaProp {
     /// - NEVER included on standard visits
     /// - OPTIONALLY included on partial reloads
     /// - ONLY evaluated when needed
-    Demand(Arc<dyn Fn() -> Value + Send + Sync>),
+    Demand(PropResolver),
+    /// - NEVER included on standard visits (listed under the page's `deferredProps` instead)
+    /// - OPTIONALLY included on partial reloads
+    /// - ONLY evaluated when needed
+    ///
+    /// The second field is the group name. Props in the same group are requested together by
+    /// the client-side adapter. If `None` is given, the prop is put in the `default` group.
+    Deferred(PropResolver, Option<String>),
+    /// - ALWAYS included on standard visits
+    /// - OPTIONALLY included on partial reloads
+    /// - ALWAYS evaluated
+    ///
+    /// Listed under the page's `mergeProps`, so that the client-side adapter appends it to
+    /// the current value instead of replacing it (unless the client asks it to be reset).
+    Merge(Value),
+    /// - ALWAYS included on standard visits
+    /// - OPTIONALLY included on partial reloads
+    /// - ALWAYS evaluated
+    ///
+    /// Listed under the page's `prependProps`, so that the client-side adapter prepends it to
+    /// the current value instead of replacing it (unless the client asks it to be reset).
+    Prepend(Value),
+    /// - ALWAYS included on standard visits
+    /// - OPTIONALLY included on partial reloads
+    /// - ALWAYS evaluated
+    ///
+    /// Listed under the page's `deepMergeProps`, so that the client-side adapter recursively
+    /// merges it into the current value (unless the client asks it to be reset).
+    ///
+    /// The second field holds the paths, relative to the prop, of the keys the client must use
+    /// to match the merged items and avoid duplicates. E.g., `vec!["data.id".into()]` for a
+    /// paginated `{ data, meta }` object.
+    DeepMerge(Value, Vec<String>),
+    /// - ALWAYS included on standard visits, unless the client already holds it
+    /// - OPTIONALLY included on partial reloads (even if the client already holds it,
+    ///   as long as it's explicitly requested)
+    /// - ONLY evaluated when included
+    ///
+    /// Listed under the page's `onceProps`, so that the client-side adapter keeps its value
+    /// between visits and tells the server (through the `X-Inertia-Except-Once-Props` header)
+    /// it doesn't need to be sent again.
+    Once(PropResolver),
+    /// - ALWAYS included on standard visits
+    /// - OPTIONALLY included on partial reloads
+    /// - ONLY evaluated when included
+    ///
+    /// A paginated collection loaded by the client-side adapter's infinite scrolling. The
+    /// resolver must return an object whose `data` key holds the current page's items, which
+    /// are appended or prepended to the loaded ones, depending on the direction the client is
+    /// scrolling to (unless the client asks it to be reset).
+    ///
+    /// The second field holds the pagination metadata, listed under the page's `scrollProps`.
+    Scroll(PropResolver, ScrollProp),
+}
+
+impl InertiaProp {
+    /// Creates a prop whose value is cached between requests for `ttl`, under `key`. Useful for
+    /// expensive props shared by many pages, such as navigation menus. Otherwise, it behaves
+    /// like an [`InertiaProp::Lazy`].
+    ///
+    /// Cached values can be invalidated with [`Inertia::invalidate_cached_prop`]. See
+    /// [`PropResolver::cached`] before caching props that depend on the current user.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use inertia_rust::{InertiaProp, PropResolver};
+    /// use serde_json::json;
+    /// use std::time::Duration;
+    ///
+    /// let menu = InertiaProp::cached(
+    ///     "navigation_menu",
+    ///     Duration::from_secs(60),
+    ///     PropResolver::new(|| json!(["Home", "Blog"])),
+    /// );
+    /// ```
+    ///
+    /// [`Inertia::invalidate_cached_prop`]: crate::Inertia::invalidate_cached_prop
+    pub fn cached(key: impl ToString, ttl: Duration, resolver: PropResolver) -> Self {
+        InertiaProp::Lazy(resolver.cached(key, ttl))
+    }
+}
+
+/// Metadata of a once prop, as sent in the page's `onceProps` field.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct OnceProp {
+    /// The key of the prop the client-side adapter must keep.
+    pub prop: String,
+    /// The timestamp, in milliseconds, after which the client must request the prop again.
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<u64>,
+}
+
+/// Pagination metadata of a scroll prop, as sent in the page's `scrollProps` field.
+///
+/// # Examples
+/// ```rust
+/// use inertia_rust::{InertiaProp, PropResolver, ScrollProp};
+/// use serde_json::json;
+///
+/// let feed = InertiaProp::Scroll(
+///     PropResolver::new(|| json!({"data": [{"id": 21}, {"id": 22}]})),
+///     ScrollProp::new("page", 2).with_previous_page(1).with_next_page(3),
+/// );
+/// ```
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct ScrollProp {
+    /// The query string parameter the client-side adapter uses to request other pages.
+    #[serde(rename = "pageName")]
+    pub page_name: String,
+    #[serde(rename = "previousPage")]
+    pub previous_page: Option<Value>,
+    #[serde(rename = "nextPage")]
+    pub next_page: Option<Value>,
+    #[serde(rename = "currentPage")]
+    pub current_page: Option<Value>,
+    /// Whether the client-side adapter must discard the pages it has already loaded. It's set
+    /// when the client asks the prop to be reset.
+    #[serde(default)]
+    pub reset: bool,
+}
+
+impl ScrollProp {
+    /// Creates the metadata of a page without neighbours. Pages may be numbers or cursors.
+    ///
+    /// # Arguments
+    /// * `page_name`       -   The query string parameter used to request other pages.
+    /// * `current_page`    -   The page being sent.
+    pub fn new(page_name: impl ToString, current_page: impl Into<Value>) -> Self {
+        Self {
+            page_name: page_name.to_string(),
+            previous_page: None,
+            next_page: None,
+            current_page: Some(current_page.into()),
+            reset: false,
+        }
+    }
+
+    pub fn with_previous_page(mut self, previous_page: impl Into<Value>) -> Self {
+        self.previous_page = Some(previous_page.into());
+        self
+    }
+
+    pub fn with_next_page(mut self, next_page: impl Into<Value>) -> Self {
+        self.next_page = Some(next_page.into());
+        self
+    }
+}
+
+/// The key, inside a scroll prop's value, of the items that must be merged.
+pub(crate) const SCROLL_PROP_DATA_KEY: &str = "data";
+
+/// Keys of the mergeable props included in a response, split by how the client-side
+/// adapter must merge them with the props it already holds.
+#[derive(Default, Debug, PartialEq, Eq)]
+pub(crate) struct MergeableProps {
+    pub merge: Vec<String>,
+    pub prepend: Vec<String>,
+    pub deep_merge: Vec<String>,
+    /// Full paths (prefixed by the prop key) of the keys used to match merged items.
+    pub match_on: Vec<String>,
+}
+
+impl MergeableProps {
+    pub fn extend(&mut self, other: MergeableProps) {
+        self.merge.extend(other.merge);
+        self.prepend.extend(other.prepend);
+        self.deep_merge.extend(other.deep_merge);
+        self.match_on.extend(other.match_on);
+    }
+}
+
+/// How the shared props (set by the middleware) and the page props (given to the render
+/// methods) are merged when both have a prop with the same key.
+///
+/// Collisions are logged as warnings in debug builds, whatever the strategy is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PropsMergeStrategy {
+    /// Keeps the page prop.
+    #[default]
+    PageWins,
+    /// Keeps the shared prop.
+    SharedWins,
+    /// Recursively merges both props if they are objects, keeping the page prop's values
+    /// when a nested key collides. Props that aren't both objects behave like `PageWins`.
+    DeepMerge,
+    /// Fails the render with an [`InertiaError::PropsConflictError`] naming the colliding prop.
+    Error,
+}
+
+impl PropsMergeStrategy {
+    /// Settles the collisions between the shared props and the page props before they are
+    /// resolved, dropping the props that lose. Returns the keys of the props kept on both
+    /// sides, which must be deep-merged once resolved.
+    ///
+    /// # Errors
+    /// Returns an [`InertiaError::PropsConflictError`] if a prop collides and the strategy is
+    /// [`PropsMergeStrategy::Error`].
+    pub(crate) fn settle_collisions<P, S>(
+        &self,
+        page_props: &mut HashMap<String, P>,
+        shared_props: &mut HashMap<String, S>,
+    ) -> Result<Vec<String>, InertiaError> {
+        let mut collisions = shared_props
+            .keys()
+            .filter(|key| page_props.contains_key(*key))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        collisions.sort();
+
+        if cfg!(debug_assertions) {
+            for key in &collisions {
+                log::warn!(
+                    "{}",
+                    inertia_err_msg(format!(
+                        "Prop \"{}\" is both a shared prop and a page prop. Merging them with {:?}.",
+                        key, self
+                    ))
+                );
+            }
+        }
+
+        match self {
+            PropsMergeStrategy::PageWins => {
+                for key in &collisions {
+                    shared_props.remove(key);
+                }
+                Ok(Vec::new())
+            }
+            PropsMergeStrategy::SharedWins => {
+                for key in &collisions {
+                    page_props.remove(key);
+                }
+                Ok(Vec::new())
+            }
+            PropsMergeStrategy::DeepMerge => Ok(collisions),
+            PropsMergeStrategy::Error => match collisions.into_iter().next() {
+                Some(key) => Err(InertiaError::PropsConflictError(key)),
+                None => Ok(Vec::new()),
+            },
+        }
+    }
+
+    /// Merges the resolved shared props into the resolved page props.
+    ///
+    /// # Errors
+    /// Returns an [`InertiaError::PropsConflictError`] if a prop collides and the strategy is
+    /// [`PropsMergeStrategy::Error`].
+    pub(crate) fn merge(
+        &self,
+        page_props: &mut Map<String, Value>,
+        shared_props: Map<String, Value>,
+    ) -> Result<(), InertiaError> {
+        for (key, shared_value) in shared_props {
+            let Some(page_value) = page_props.get_mut(&key) else {
+                page_props.insert(key, shared_value);
+                continue;
+            };
+
+            match self {
+                PropsMergeStrategy::PageWins => {}
+                PropsMergeStrategy::SharedWins => *page_value = shared_value,
+                PropsMergeStrategy::DeepMerge => deep_merge_values(page_value, shared_value),
+                PropsMergeStrategy::Error => return Err(InertiaError::PropsConflictError(key)),
+            }
+        }
+
+        Ok(())
+    }
 }
 
+pub(crate) const DEFAULT_DEFERRED_GROUP: &str = "default";
+
 impl InertiaProp {
+    /// Resolves the props that must be sent in the response. Every resolver of the included
+    /// props is awaited concurrently.
+    ///
+    /// # Errors
+    /// Returns an [`InertiaError::PropError`] if any resolver fails and its error policy is
+    /// [`PropErrorPolicy::Fail`].
     #[inline]
-    pub(crate) fn resolve_props(
-        raw_props: &InertiaProps,
-        req_type: InertiaRequestType,
-    ) -> Map<String, Value> {
+    pub(crate) async fn resolve_props<P: Borrow<InertiaProp>>(
+        raw_props: &HashMap<String, P>,
+        ctx: &InertiaRequestContext,
+    ) -> Result<Map<String, Value>, InertiaError> {
         let mut props = Map::new();
+        let mut resolvers = Vec::new();
+
+        for (key, value) in raw_props.iter() {
+            let value = value.borrow();
 
-        if req_type.is_standard() {
-            for (key, value) in raw_props.iter() {
-                if let InertiaProp::Demand(_) = value {
+            if !value.should_be_included(key, ctx.req_type(), ctx.except_once_props()) {
+                continue;
+            }
+
+            match value {
+                InertiaProp::Data(value)
+                | InertiaProp::Always(value)
+                | InertiaProp::Merge(value)
+                | InertiaProp::Prepend(value)
+                | InertiaProp::DeepMerge(value, _) => {
+                    props.insert(key.clone(), value.clone());
+                }
+                InertiaProp::Lazy(resolver)
+                | InertiaProp::Demand(resolver)
+                | InertiaProp::Deferred(resolver, _)
+                | InertiaProp::Once(resolver)
+                | InertiaProp::Scroll(resolver, _) => {
+                    resolvers.push((key, resolver));
+                }
+            };
+        }
+
+        let values = join_all(
+            resolvers
+                .iter()
+                .map(|(key, resolver)| resolver.resolve(key, ctx)),
+        )
+        .await;
+
+        for ((key, _), value) in resolvers.into_iter().zip(values) {
+            props.insert(key.clone(), value?);
+        }
+
+        // nested keys (e.g., "user.permissions") requested or excepted by partial reloads
+        // are only applied after the props have been resolved
+        if let Some(partials) = ctx.req_type().partials() {
+            for (key, value) in props.iter_mut() {
+                if let Some(InertiaProp::Always(_)) = raw_props.get(key).map(Borrow::borrow) {
                     continue;
                 }
 
-                props.insert(key.clone(), value.clone().resolve_prop_unconditionally());
+                Self::filter_nested_props(key, value, partials);
             }
+        }
+
+        Ok(props)
+    }
 
-            return props;
+    #[inline]
+    fn should_be_included(
+        &self,
+        key: &String,
+        req_type: &InertiaRequestType,
+        except_once_props: &[String],
+    ) -> bool {
+        let partials = match req_type.partials() {
+            None => {
+                return match self {
+                    InertiaProp::Demand(_) | InertiaProp::Deferred(_, _) => false,
+                    InertiaProp::Once(_) => !except_once_props.contains(key),
+                    _ => true,
+                };
+            }
+            Some(partials) => partials,
+        };
+
+        match self {
+            InertiaProp::Always(_) => true,
+            InertiaProp::Once(_) => {
+                let is_held = except_once_props.contains(key)
+                    && !partials.only.iter().any(|path| Self::is_path_of(path, key));
+                Self::should_be_pushed(key, partials) && !is_held
+            }
+            _ => Self::should_be_pushed(key, partials),
         }
+    }
 
-        let partials = req_type.unwrap_partial();
+    /// Lists the once props that the client-side adapter must keep after this response. Once
+    /// props skipped because the client already holds them are also listed, so that the client
+    /// doesn't forget them.
+    #[inline]
+    pub(crate) fn resolve_once_props<P: Borrow<InertiaProp>>(
+        raw_props: &HashMap<String, P>,
+        req_type: &InertiaRequestType,
+    ) -> HashMap<String, OnceProp> {
+        let mut once_props = HashMap::new();
+        let partials = req_type.partials();
 
         for (key, value) in raw_props.iter() {
-            match value {
-                InertiaProp::Always(value) => {
-                    props.insert(key.clone(), value.clone());
-                }
-                InertiaProp::Data(value) => {
-                    if Self::should_be_pushed(key, &partials) {
-                        props.insert(key.clone(), value.clone());
-                    }
+            let value = value.borrow();
+
+            if let InertiaProp::Once(_) = value {
+                if partials.is_some_and(|partials| !Self::should_be_pushed(key, partials)) {
+                    continue;
                 }
-                InertiaProp::Lazy(resolver) => {
-                    if Self::should_be_pushed(key, &partials) {
-                        props.insert(key.clone(), resolver());
-                    }
+
+                once_props.insert(
+                    key.clone(),
+                    OnceProp {
+                        prop: key.clone(),
+                        expires_at: None,
+                    },
+                );
+            }
+        }
+
+        once_props
+    }
+
+    /// Lists the mergeable props included in the response. Props the client has asked to be
+    /// reset (through the `X-Inertia-Reset` header) are left out, so that they get replaced.
+    #[inline]
+    pub(crate) fn resolve_mergeable_props<P: Borrow<InertiaProp>>(
+        raw_props: &HashMap<String, P>,
+        req_type: &InertiaRequestType,
+        reset: &[String],
+    ) -> MergeableProps {
+        let mut mergeable_props = MergeableProps::default();
+        let partials = req_type.partials();
+
+        let scroll_merge_intent = partials
+            .map(|partials| partials.scroll_merge_intent)
+            .unwrap_or_default();
+
+        for (key, value) in raw_props.iter() {
+            let value = value.borrow();
+
+            let (keys, merged_key, match_on) = match value {
+                InertiaProp::Merge(_) => (&mut mergeable_props.merge, key.clone(), None),
+                InertiaProp::Prepend(_) => (&mut mergeable_props.prepend, key.clone(), None),
+                InertiaProp::DeepMerge(_, match_on) => {
+                    (&mut mergeable_props.deep_merge, key.clone(), Some(match_on))
                 }
-                InertiaProp::Demand(resolver) => {
-                    if Self::should_be_pushed(key, &partials) {
-                        props.insert(key.clone(), resolver());
-                    }
+                InertiaProp::Scroll(_, _) => {
+                    let keys = match scroll_merge_intent {
+                        ScrollMergeIntent::Append => &mut mergeable_props.merge,
+                        ScrollMergeIntent::Prepend => &mut mergeable_props.prepend,
+                    };
+
+                    (keys, format!("{}.{}", key, SCROLL_PROP_DATA_KEY), None)
                 }
+                _ => continue,
             };
+
+            if partials.is_some_and(|partials| !Self::should_be_pushed(key, partials))
+                || reset.contains(key)
+            {
+                continue;
+            }
+
+            keys.push(merged_key);
+
+            if let Some(match_on) = match_on {
+                mergeable_props
+                    .match_on
+                    .extend(match_on.iter().map(|path| format!("{}.{}", key, path)));
+            }
         }
 
-        props
+        mergeable_props.merge.sort();
+        mergeable_props.prepend.sort();
+        mergeable_props.deep_merge.sort();
+        mergeable_props.match_on.sort();
+
+        mergeable_props
     }
 
+    /// Lists the pagination metadata of the scroll props included in the response, flagging
+    /// the ones the client has asked to be reset.
     #[inline]
-    fn resolve_prop_unconditionally(self) -> Value {
-        match self {
-            InertiaProp::Always(value) => value,
-            InertiaProp::Data(value) => value,
-            InertiaProp::Demand(resolver) => resolver(),
-            InertiaProp::Lazy(resolver) => resolver(),
+    pub(crate) fn resolve_scroll_props<P: Borrow<InertiaProp>>(
+        raw_props: &HashMap<String, P>,
+        req_type: &InertiaRequestType,
+        reset: &[String],
+    ) -> HashMap<String, ScrollProp> {
+        let mut scroll_props = HashMap::new();
+        let partials = req_type.partials();
+
+        for (key, value) in raw_props.iter() {
+            let value = value.borrow();
+
+            if let InertiaProp::Scroll(_, metadata) = value {
+                if partials.is_some_and(|partials| !Self::should_be_pushed(key, partials)) {
+                    continue;
+                }
+
+                let mut metadata = metadata.clone();
+                metadata.reset = reset.contains(key);
+                scroll_props.insert(key.clone(), metadata);
+            }
         }
+
+        scroll_props
+    }
+
+    /// Groups the deferred props' keys by their group names, so that they can be sent
+    /// in the page's `deferredProps` field. Deferred props are only listed on standard visits,
+    /// since partial reloads are the ones triggered by the client-side to fetch them.
+    #[inline]
+    pub(crate) fn resolve_deferred_props<P: Borrow<InertiaProp>>(
+        raw_props: &HashMap<String, P>,
+        req_type: &InertiaRequestType,
+    ) -> HashMap<String, Vec<String>> {
+        let mut deferred_props = HashMap::<String, Vec<String>>::new();
+
+        if req_type.is_partial() {
+            return deferred_props;
+        }
+
+        for (key, value) in raw_props.iter() {
+            let value = value.borrow();
+
+            if let InertiaProp::Deferred(_, group) = value {
+                let group = group.as_deref().unwrap_or(DEFAULT_DEFERRED_GROUP);
+                deferred_props
+                    .entry(group.to_string())
+                    .or_default()
+                    .push(key.clone());
+            }
+        }
+
+        for keys in deferred_props.values_mut() {
+            keys.sort();
+        }
+
+        deferred_props
     }
 
     #[inline]
     fn should_be_pushed(key: &String, partial: &PartialComponent) -> bool {
-        partial.only.contains(key) || partial.only.is_empty() && !partial.except.contains(key)
+        if !partial.only.is_empty() {
+            return partial.only.iter().any(|path| Self::is_path_of(path, key));
+        }
+
+        !partial.except.contains(key)
+    }
+
+    /// Checks whether a dot-notated `path` (e.g. "user.permissions") points to the prop `key`
+    /// or to any of its nested keys.
+    #[inline]
+    fn is_path_of(path: &str, key: &str) -> bool {
+        path.strip_prefix(key)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    }
+
+    /// Keeps only the nested keys of `value` requested by the partial reload, then removes
+    /// the excepted ones. Props requested or excepted as a whole are left untouched.
+    fn filter_nested_props(key: &str, value: &mut Value, partial: &PartialComponent) {
+        let nested_paths = |paths: &[String]| -> Option<Vec<Vec<String>>> {
+            let mut nested_paths = Vec::new();
+
+            for path in paths.iter() {
+                if path == key {
+                    return None;
+                }
+
+                if let Some(nested_path) = path.strip_prefix(key).and_then(|p| p.strip_prefix('.'))
+                {
+                    nested_paths.push(nested_path.split('.').map(String::from).collect());
+                }
+            }
+
+            Some(nested_paths)
+        };
+
+        if let Some(only) = nested_paths(&partial.only) {
+            if !only.is_empty() {
+                let mut filtered_value = Value::Object(Map::new());
+
+                for path in only.iter() {
+                    pick_nested_value(value, &mut filtered_value, path);
+                }
+
+                *value = filtered_value;
+            }
+        }
+
+        if let Some(except) = nested_paths(&partial.except) {
+            for path in except.iter() {
+                remove_nested_value(value, path);
+            }
+        }
+    }
+}
+
+/// Copies the value found at `path` inside `source` into the same path inside `target`.
+fn pick_nested_value(source: &Value, target: &mut Value, path: &[String]) {
+    let (segment, rest) = match path.split_first() {
+        None => return,
+        Some(split) => split,
+    };
+
+    let (Some(source_value), Value::Object(target)) = (source.get(segment), target) else {
+        return;
+    };
+
+    if rest.is_empty() {
+        target.insert(segment.clone(), source_value.clone());
+        return;
+    }
+
+    let target_value = target
+        .entry(segment.clone())
+        .or_insert_with(|| Value::Object(Map::new()));
+
+    pick_nested_value(source_value, target_value, rest);
+}
+
+/// Removes the value found at `path` inside `value`, if there is any.
+fn remove_nested_value(value: &mut Value, path: &[String]) {
+    let (segment, rest) = match path.split_first() {
+        None => return,
+        Some(split) => split,
+    };
+
+    let Value::Object(object) = value else {
+        return;
+    };
+
+    if rest.is_empty() {
+        object.remove(segment);
+    } else if let Some(nested_value) = object.get_mut(segment) {
+        remove_nested_value(nested_value, rest);
+    }
+}
+
+/// Recursively merges `source` into `target`, keeping `target`'s values on collisions that
+/// aren't between two objects.
+fn deep_merge_values(target: &mut Value, source: Value) {
+    let (Value::Object(target), Value::Object(source)) = (target, source) else {
+        return;
+    };
+
+    for (key, source_value) in source {
+        match target.get_mut(&key) {
+            None => {
+                target.insert(key, source_value);
+            }
+            Some(target_value) => deep_merge_values(target_value, source_value),
+        }
     }
 }
 
 pub type InertiaProps = HashMap<String, InertiaProp>;
+
+/// Converts a typed struct into [`InertiaProps`]. Rather than implementing it by hand, you
+/// might prefer deriving it with `#[derive(InertiaProps)]` (requires the `macros` feature).
+///
+/// # Examples
+/// ```ignore
+/// use inertia_rust::{InertiaProps, IntoInertiaProps};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// #[derive(InertiaProps)]
+/// struct UsersIndexProps {
+///     #[inertia(always)]
+///     title: String,
+///     #[inertia(lazy, rename = "allUsers")]
+///     users: Vec<User>,
+/// }
+/// ```
+pub trait IntoInertiaProps {
+    /// # Errors
+    /// Returns an [`InertiaError::SerializationError`] if any eagerly evaluated field can't be
+    /// serialized into a JSON value.
+    fn into_inertia_props(self) -> Result<InertiaProps, InertiaError>;
+}
+
+#[cfg(test)]
+mod test {
+    use super::PropsMergeStrategy;
+    use crate::InertiaError;
+    use serde_json::{json, Map, Value};
+    use std::collections::HashMap;
+
+    fn merge(strategy: PropsMergeStrategy) -> Result<Value, InertiaError> {
+        let mut page_props: Map<String, Value> = serde_json::from_value(json!({
+            "auth": {"user": "John Doe", "permissions": {"posts": ["read"]}},
+            "title": "Dashboard",
+        }))
+        .unwrap();
+
+        let shared_props: Map<String, Value> = serde_json::from_value(json!({
+            "auth": {"user": "Jane Doe", "permissions": {"users": ["read"]}},
+            "flash": "Welcome!",
+        }))
+        .unwrap();
+
+        strategy.merge(&mut page_props, shared_props)?;
+        Ok(Value::Object(page_props))
+    }
+
+    #[test]
+    fn test_props_merge_strategies() {
+        assert_eq!(
+            json!({
+                "auth": {"user": "John Doe", "permissions": {"posts": ["read"]}},
+                "title": "Dashboard",
+                "flash": "Welcome!",
+            }),
+            merge(PropsMergeStrategy::PageWins).unwrap()
+        );
+
+        assert_eq!(
+            json!({
+                "auth": {"user": "Jane Doe", "permissions": {"users": ["read"]}},
+                "title": "Dashboard",
+                "flash": "Welcome!",
+            }),
+            merge(PropsMergeStrategy::SharedWins).unwrap()
+        );
+
+        assert_eq!(
+            json!({
+                "auth": {
+                    "user": "John Doe",
+                    "permissions": {"posts": ["read"], "users": ["read"]}
+                },
+                "title": "Dashboard",
+                "flash": "Welcome!",
+            }),
+            merge(PropsMergeStrategy::DeepMerge).unwrap()
+        );
+
+        assert!(matches!(
+            merge(PropsMergeStrategy::Error),
+            Err(InertiaError::PropsConflictError(key)) if key == "auth"
+        ));
+    }
+
+    #[test]
+    fn test_settle_props_collisions() {
+        let settle = |strategy: PropsMergeStrategy| {
+            let mut page_props =
+                HashMap::from([("auth", 1), ("title", 1)].map(|(k, v)| (k.to_string(), v)));
+            let mut shared_props =
+                HashMap::from([("auth", 2), ("flash", 2)].map(|(k, v)| (k.to_string(), v)));
+
+            strategy
+                .settle_collisions(&mut page_props, &mut shared_props)
+                .map(|deep_merged_keys| {
+                    let mut page_keys = page_props.into_keys().collect::<Vec<_>>();
+                    let mut shared_keys = shared_props.into_keys().collect::<Vec<_>>();
+                    page_keys.sort();
+                    shared_keys.sort();
+                    (page_keys, shared_keys, deep_merged_keys)
+                })
+        };
+
+        // the losing props are dropped, so that their metadata isn't sent
+        assert_eq!(
+            (
+                vec!["auth".into(), "title".into()],
+                vec!["flash".into()],
+                vec![]
+            ),
+            settle(PropsMergeStrategy::PageWins).unwrap()
+        );
+
+        assert_eq!(
+            (
+                vec!["title".into()],
+                vec!["auth".into(), "flash".into()],
+                vec![]
+            ),
+            settle(PropsMergeStrategy::SharedWins).unwrap()
+        );
+
+        assert_eq!(
+            (
+                vec!["auth".into(), "title".into()],
+                vec!["auth".into(), "flash".into()],
+                vec!["auth".into()]
+            ),
+            settle(PropsMergeStrategy::DeepMerge).unwrap()
+        );
+
+        assert!(matches!(
+            settle(PropsMergeStrategy::Error),
+            Err(InertiaError::PropsConflictError(key)) if key == "auth"
+        ));
+    }
+}
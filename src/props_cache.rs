@@ -0,0 +1,54 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A store that keeps the values of cached props between requests. See
+/// [`InertiaProp::cached`].
+///
+/// `Inertia` uses an [`InMemoryPropsCache`] unless another store is given to its
+/// configuration. Implement this trait to back the cached props with another store.
+///
+/// [`InertiaProp::cached`]: crate::InertiaProp::cached
+pub trait PropsCache: Send + Sync {
+    /// Gets the value cached under `key`, if it hasn't expired yet.
+    fn get(&self, key: &str) -> Option<Value>;
+
+    /// Caches `value` under `key` for `ttl`.
+    fn set(&self, key: &str, value: Value, ttl: Duration);
+
+    /// Removes the value cached under `key`, so that it gets resolved again.
+    fn invalidate(&self, key: &str);
+}
+
+/// A [`PropsCache`] that keeps the values in the application's memory.
+#[derive(Default)]
+pub struct InMemoryPropsCache {
+    entries: Mutex<HashMap<String, (Value, Instant)>>,
+}
+
+impl PropsCache for InMemoryPropsCache {
+    fn get(&self, key: &str) -> Option<Value> {
+        let mut entries = self.entries.lock().unwrap();
+
+        match entries.get(key) {
+            Some((value, expires_at)) if *expires_at > Instant::now() => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn set(&self, key: &str, value: Value, ttl: Duration) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), (value, Instant::now() + ttl));
+    }
+
+    fn invalidate(&self, key: &str) {
+        self.entries.lock().unwrap().remove(key);
+    }
+}
@@ -0,0 +1,174 @@
+use super::headers;
+use super::response::InertiaResponseBuilder;
+use crate::utils::inertia_err_msg;
+use crate::{Component, InertiaProp, InertiaProps};
+use actix_web::dev::ServiceResponse;
+use actix_web::http::StatusCode;
+use actix_web::middleware::{ErrorHandlerResponse, ErrorHandlers};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// The component rendered by [`InertiaErrorHandlers`] if none is given.
+pub const DEFAULT_ERROR_COMPONENT: &str = "Errors/Show";
+
+/// Builds an actix [`ErrorHandlers`] middleware that replaces error responses with an Inertia
+/// page, rendered with the original status and the `status` and `message` props.
+///
+/// By default, `404`, `403`, `500` and `503` responses are handled, with their canonical
+/// reasons as messages. This includes the responses of handlers that failed with a
+/// `ResponseError`. In debug builds, the error's own message is sent instead of the canonical
+/// reason, unless a message has been given through [`InertiaErrorHandlers::with_status`].
+/// Responses that already are Inertia pages are left untouched, and the data flashed to the
+/// client is kept for the next page.
+///
+/// # Examples
+/// ```ignore
+/// use actix_web::{http::StatusCode, App};
+/// use inertia_rust::actix::InertiaErrorHandlers;
+///
+/// let app = App::new().wrap(
+///     InertiaErrorHandlers::<Vite>::new()
+///         .with_status(StatusCode::NOT_FOUND, "Sorry, we couldn't find this page.")
+///         .without_status(StatusCode::SERVICE_UNAVAILABLE)
+///         .disabled_in_debug()
+///         .build(),
+/// );
+/// ```
+pub struct InertiaErrorHandlers<T>
+where
+    T: 'static,
+{
+    component: Component,
+    messages: HashMap<StatusCode, Option<String>>,
+    disabled_in_debug: bool,
+    _template_resolver_data: PhantomData<T>,
+}
+
+impl<T> Default for InertiaErrorHandlers<T>
+where
+    T: 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> InertiaErrorHandlers<T>
+where
+    T: 'static,
+{
+    pub fn new() -> Self {
+        let messages = [
+            StatusCode::NOT_FOUND,
+            StatusCode::FORBIDDEN,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::SERVICE_UNAVAILABLE,
+        ]
+        .into_iter()
+        .map(|status| (status, None))
+        .collect();
+
+        Self {
+            component: Component(DEFAULT_ERROR_COMPONENT.into()),
+            messages,
+            disabled_in_debug: false,
+            _template_resolver_data: PhantomData,
+        }
+    }
+
+    /// Sets the component of the error pages. Defaults to `"Errors/Show"`.
+    pub fn with_component(mut self, component: Component) -> Self {
+        self.component = component;
+        self
+    }
+
+    /// Handles responses with the given status, rendering the given message.
+    pub fn with_status(mut self, status: StatusCode, message: impl ToString) -> Self {
+        self.messages.insert(status, Some(message.to_string()));
+        self
+    }
+
+    /// Stops handling responses with the given status.
+    pub fn without_status(mut self, status: StatusCode) -> Self {
+        self.messages.remove(&status);
+        self
+    }
+
+    /// Lets error responses through untouched in debug builds, so that the framework's own error
+    /// messages can be inspected.
+    pub fn disabled_in_debug(mut self) -> Self {
+        self.disabled_in_debug = true;
+        self
+    }
+
+    pub fn build<B>(self) -> ErrorHandlers<B>
+    where
+        B: 'static,
+    {
+        let mut handlers = ErrorHandlers::new();
+
+        if self.disabled_in_debug && cfg!(debug_assertions) {
+            return handlers;
+        }
+
+        for (status, message) in self.messages {
+            let component = self.component.clone();
+            handlers = handlers.handler(status, move |res| {
+                render_error_page::<T, B>(res, component.clone(), status, message.clone())
+            });
+        }
+
+        handlers
+    }
+}
+
+fn render_error_page<T, B>(
+    res: ServiceResponse<B>,
+    component: Component,
+    status: StatusCode,
+    message: Option<String>,
+) -> actix_web::Result<ErrorHandlerResponse<B>>
+where
+    T: 'static,
+    B: 'static,
+{
+    if res.headers().contains_key(headers::X_INERTIA) {
+        return Ok(ErrorHandlerResponse::Response(res.map_into_left_body()));
+    }
+
+    let message = match (message, res.response().error()) {
+        (Some(message), _) => message,
+        (None, Some(err)) if cfg!(debug_assertions) => err.to_string(),
+        (None, _) => default_message(status),
+    };
+
+    let req = res.request().clone();
+
+    Ok(ErrorHandlerResponse::Future(Box::pin(async move {
+        let mut props: InertiaProps = HashMap::new();
+        props.insert("status".into(), InertiaProp::Data(status.as_u16().into()));
+        props.insert("message".into(), InertiaProp::Data(message.into()));
+
+        let page = InertiaResponseBuilder::<T>::new(&req, component)
+            .status(status)
+            .without_flash()
+            .with_props(props)
+            .render()
+            .await;
+
+        match page {
+            Ok(page) => Ok(ServiceResponse::new(req, page).map_into_right_body()),
+            Err(err) => {
+                log::error!(
+                    "{}",
+                    inertia_err_msg(format!("Failed to render the error page. {}", err))
+                );
+                Ok(res.map_into_left_body())
+            }
+        }
+    })))
+}
+
+fn default_message(status: StatusCode) -> String {
+    status.canonical_reason().unwrap_or("Error").to_string()
+}
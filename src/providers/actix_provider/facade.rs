@@ -1,8 +1,19 @@
-use crate::inertia::InertiaResponder;
+use super::impls::InertiaActixHelpers;
+use super::redirect::{ExternalRedirect, InertiaRedirect};
+use super::response::InertiaResponseBuilder;
+use crate::inertia::{InertiaHttpRequest, InertiaResponder};
 use crate::utils::inertia_err_msg;
 use crate::{Component, Inertia, InertiaError, InertiaProps};
 use actix_web::web::Data;
-use actix_web::{HttpRequest, HttpResponse};
+use actix_web::{HttpMessage, HttpRequest, HttpResponse};
+use serde::Serialize;
+
+/// Per-response overrides of the history encryption behavior, stored in the request extensions.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct HistoryOptions {
+    pub encrypt: Option<bool>,
+    pub clear: bool,
+}
 
 /// Short for calling `render` from the `Inertia` instance configured and added to the request
 /// AppData.
@@ -45,7 +56,199 @@ where
     inertia.render_with_props(req, component, props).await
 }
 
-fn extract_inertia<T>(req: &HttpRequest) -> &Inertia<T>
+/// Short for calling `render_with_struct` from the `Inertia` instance configured and added to the
+/// request AppData.
+///
+/// # Arguments
+/// * `req`         -   A reference to the HttpRequest.
+/// * `component`   -   The name of the page javascript component.
+/// * `props`       -   A serializable struct whose fields are sent as the page props.
+///
+/// # Panic
+/// Panics if Inertia instance hasn't been configured (set to AppData).
+pub async fn render_with_struct<T, TProps>(
+    req: &HttpRequest,
+    component: Component,
+    props: TProps,
+) -> Result<HttpResponse, InertiaError>
+where
+    T: 'static,
+    TProps: Serialize,
+{
+    let inertia: &Inertia<T> = extract_inertia(req);
+    inertia.render_with_struct(req, component, props).await
+}
+
+/// Short for calling `back` from the `Inertia` instance configured and added to the request
+/// AppData. Redirects the user back to the previous page.
+///
+/// # Arguments
+/// * `req`         -   A reference to the HttpRequest.
+///
+/// # Panic
+/// Panics if Inertia instance hasn't been configured (set to AppData).
+pub fn back<T>(req: &HttpRequest) -> HttpResponse
+where
+    T: 'static,
+{
+    let inertia: &Inertia<T> = extract_inertia(req);
+    inertia.back(req)
+}
+
+/// Redirects the user back to the previous page, as [`back`] does, allowing errors and other
+/// data to be flashed to the next request.
+///
+/// # Arguments
+/// * `req`         -   A reference to the HttpRequest.
+///
+/// # Panic
+/// Panics if Inertia instance hasn't been configured (set to AppData).
+///
+/// # Examples
+/// ```no_run
+/// use actix_web::HttpRequest;
+/// use inertia_rust::actix::{redirect_back, InertiaRedirect};
+/// use serde_json::{json, Map};
+/// # struct Vite;
+///
+/// async fn store(req: HttpRequest) -> InertiaRedirect {
+///     let mut errors = Map::new();
+///     errors.insert("title".into(), json!("The title is required."));
+///
+///     redirect_back::<Vite>(&req)
+///         .with_errors(errors)
+///         .with_flash("warning", "Please, review your data.")
+/// }
+/// ```
+pub fn redirect_back<T>(req: &HttpRequest) -> InertiaRedirect
+where
+    T: 'static,
+{
+    let inertia: &Inertia<T> = extract_inertia(req);
+    InertiaRedirect::to(inertia.back_url(req))
+}
+
+/// Redirects to an external URL, or to a page that isn't handled by Inertia, forcing a full
+/// page visit on Inertia requests. Relative URLs are resolved against the configured Inertia
+/// `url`.
+///
+/// # Arguments
+/// * `req`         -   A reference to the HttpRequest.
+/// * `url`         -   The URL to be redirected to.
+///
+/// # Errors
+/// Returns an [`InertiaError::UrlError`] if the URL is invalid or if it isn't an `http` or
+/// `https` URL.
+///
+/// # Panic
+/// Panics if Inertia instance hasn't been configured (set to AppData).
+pub fn location<T>(req: &HttpRequest, url: &str) -> Result<ExternalRedirect, InertiaError>
+where
+    T: 'static,
+{
+    let inertia: &Inertia<T> = extract_inertia(req);
+    ExternalRedirect::resolve(inertia.url, url)
+}
+
+/// Short for calling `invalidate_cached_prop` from the `Inertia` instance configured and added
+/// to the request AppData.
+///
+/// # Arguments
+/// * `req`     -   A reference to the HttpRequest.
+/// * `key`     -   The key given to [`InertiaProp::cached`].
+///
+/// # Panic
+/// Panics if Inertia instance hasn't been configured (set to AppData).
+///
+/// [`InertiaProp::cached`]: crate::InertiaProp::cached
+pub fn invalidate_cached_prop<T>(req: &HttpRequest, key: &str)
+where
+    T: 'static,
+{
+    let inertia: &Inertia<T> = extract_inertia(req);
+    inertia.invalidate_cached_prop(key);
+}
+
+/// Checks whether the client-side adapter is prefetching the page (e.g., when a link is
+/// hovered) rather than visiting it. Handlers and shared props callbacks should skip side
+/// effects, such as recording analytics, on prefetches.
+///
+/// # Arguments
+/// * `req`     -   A reference to the HttpRequest. From a [`ServiceRequest`], use
+///                 `ServiceRequest::request`.
+///
+/// [`ServiceRequest`]: actix_web::dev::ServiceRequest
+pub fn is_prefetch(req: &HttpRequest) -> bool {
+    req.is_prefetch_request()
+}
+
+/// Creates a builder that renders an Inertia page with a custom HTTP status and extra headers.
+///
+/// # Arguments
+/// * `req`         -   A reference to the HttpRequest.
+/// * `component`   -   The name of the page javascript component.
+///
+/// # Examples
+/// ```no_run
+/// use actix_web::{http::StatusCode, HttpRequest, HttpResponse};
+/// use inertia_rust::{actix::response, InertiaError};
+/// # struct Vite;
+///
+/// async fn not_found(req: HttpRequest) -> Result<HttpResponse, InertiaError> {
+///     response::<Vite>(&req, "Errors/NotFound".into())
+///         .status(StatusCode::NOT_FOUND)
+///         .insert_header(("Cache-Control", "no-store"))
+///         .render()
+///         .await
+/// }
+/// ```
+pub fn response<T>(req: &HttpRequest, component: Component) -> InertiaResponseBuilder<'_, T>
+where
+    T: 'static,
+{
+    InertiaResponseBuilder::new(req, component)
+}
+
+/// Overrides, for the response to the given request, whether the page's history state should be
+/// encrypted by the client-side adapter. If it's never called, the `encrypt_history` option given
+/// to the Inertia configuration is used.
+///
+/// # Arguments
+/// * `req`     -   A reference to the HttpRequest.
+/// * `encrypt` -   Whether the history state must be encrypted.
+pub fn encrypt_history(req: &HttpRequest, encrypt: bool) {
+    let mut options = req
+        .extensions()
+        .get::<HistoryOptions>()
+        .copied()
+        .unwrap_or_default();
+
+    options.encrypt = Some(encrypt);
+    req.extensions_mut().insert(options);
+}
+
+/// Makes the client-side adapter clear its encrypted history states when the response to the
+/// given request is rendered. E.g., when the user logs out.
+///
+/// Note that it only affects the page rendered for this very request. If you are redirecting the
+/// user, set `clear_history` in the [`InertiaTemporarySession`] flashed to the next request.
+///
+/// # Arguments
+/// * `req`     -   A reference to the HttpRequest.
+///
+/// [`InertiaTemporarySession`]: crate::InertiaTemporarySession
+pub fn clear_history(req: &HttpRequest) {
+    let mut options = req
+        .extensions()
+        .get::<HistoryOptions>()
+        .copied()
+        .unwrap_or_default();
+
+    options.clear = true;
+    req.extensions_mut().insert(options);
+}
+
+pub(crate) fn extract_inertia<T>(req: &HttpRequest) -> &Inertia<T>
 where
     T: 'static,
 {
@@ -16,6 +16,18 @@ pub const X_INERTIA_PARTIAL_DATA: HeaderName =
 #[allow(unused)]
 pub const X_INERTIA_PARTIAL_EXCEPT: HeaderName =
     HeaderName::from_static(inertia::X_INERTIA_PARTIAL_EXCEPT);
+#[allow(unused)]
+pub const X_INERTIA_RESET: HeaderName = HeaderName::from_static(inertia::X_INERTIA_RESET);
+#[allow(unused)]
+pub const X_INERTIA_EXCEPT_ONCE_PROPS: HeaderName =
+    HeaderName::from_static(inertia::X_INERTIA_EXCEPT_ONCE_PROPS);
+#[allow(unused)]
+pub const X_INERTIA_ERROR_BAG: HeaderName = HeaderName::from_static(inertia::X_INERTIA_ERROR_BAG);
+#[allow(unused)]
+pub const X_INERTIA_INFINITE_SCROLL_MERGE_INTENT: HeaderName =
+    HeaderName::from_static(inertia::X_INERTIA_INFINITE_SCROLL_MERGE_INTENT);
+#[allow(unused)]
+pub const PURPOSE: HeaderName = HeaderName::from_static(inertia::PURPOSE);
 
 pub enum InertiaHeader<'a> {
     Inertia,
@@ -1,23 +1,29 @@
+use super::facade::HistoryOptions;
 use super::headers;
-use super::middleware::SharedProps;
+use super::middleware::{pull_flashed_props, SharedProps};
+use super::response::ResponseOptions;
 
+use crate::inertia::{self, PREFETCH_PURPOSE};
 use crate::inertia::{Inertia, InertiaHttpRequest, InertiaResponder, InertiaService, ViewData};
 use crate::props::InertiaProp;
 use crate::props::InertiaProps;
-use crate::req_type::{InertiaRequestType, PartialComponent};
-use crate::utils::convert_struct_to_stringified_json;
+use crate::req_type::{InertiaRequestType, PartialComponent, ScrollMergeIntent};
+use crate::request_context::InertiaRequestContext;
+use crate::utils::{convert_struct_to_json_bytes, convert_struct_to_map};
 use crate::utils::{inertia_err_msg, request_page_render};
 use crate::{Component, InertiaError, InertiaPage, InertiaTemporarySession};
 
 use actix_web::body::BoxBody;
 use actix_web::dev::{ServiceFactory, ServiceRequest};
-use actix_web::http::header::HeaderName;
-use actix_web::http::StatusCode;
+use actix_web::http::header::{HeaderName, HeaderValue, CACHE_CONTROL, LOCATION, REFERER, VARY};
+use actix_web::http::{Method, StatusCode};
 use actix_web::{
     web, App, FromRequest, HttpMessage, HttpRequest, HttpResponse, HttpResponseBuilder, Responder,
     ResponseError,
 };
 use async_trait::async_trait;
+use serde::Serialize;
+use std::borrow::Cow;
 use std::collections::HashMap;
 
 impl Responder for InertiaPage {
@@ -25,14 +31,21 @@ impl Responder for InertiaPage {
 
     #[inline]
     fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
-        HttpResponseBuilder::new(StatusCode::OK)
-            .append_header(headers::InertiaHeader::Inertia.convert())
-            .body(BoxBody::new(
-                convert_struct_to_stringified_json(self).unwrap(),
-            ))
+        match convert_struct_to_json_bytes(&self) {
+            Ok(serialized_page) => page_response(serialized_page),
+            Err(err) => err.error_response(),
+        }
     }
 }
 
+/// Builds an Inertia JSON response whose body is the already serialized page, so that the
+/// page is serialized only once, straight into the body buffer.
+fn page_response(serialized_page: Vec<u8>) -> HttpResponse {
+    HttpResponseBuilder::new(StatusCode::OK)
+        .append_header(headers::InertiaHeader::Inertia.convert())
+        .body(BoxBody::new(serialized_page))
+}
+
 #[async_trait(?Send)]
 impl<T> InertiaResponder<HttpResponse, HttpRequest> for Inertia<T>
 where
@@ -47,6 +60,24 @@ where
         self.render_with_props(req, component, HashMap::new()).await
     }
 
+    #[inline]
+    async fn render_with_struct<TProps>(
+        &self,
+        req: &HttpRequest,
+        component: Component,
+        props: TProps,
+    ) -> Result<HttpResponse, InertiaError>
+    where
+        TProps: Serialize,
+    {
+        let props = convert_struct_to_map(props)?
+            .into_iter()
+            .map(|(key, value)| (key, InertiaProp::Data(value)))
+            .collect();
+
+        self.render_with_props(req, component, props).await
+    }
+
     #[inline]
     async fn render_with_props(
         &self,
@@ -54,31 +85,182 @@ where
         component: Component,
         props: InertiaProps,
     ) -> Result<HttpResponse, InertiaError> {
+        // taken before anything can fail, so that they don't leak into another render
+        let response_options = req
+            .extensions_mut()
+            .remove::<ResponseOptions>()
+            .unwrap_or_default();
+
         let url = req.uri().to_string();
-        let req_type: InertiaRequestType = req.get_request_type()?;
+        let req_type: InertiaRequestType = req.get_request_type()?.for_component(&component);
 
         if let Err(forced_refresh) = self.check_and_handle_version_mismatch(req) {
             return Ok(forced_refresh);
         };
 
-        let mut props = InertiaProp::resolve_props(&props, req_type.clone());
+        let reset = req.get_reset_props()?;
+
+        let ctx = InertiaRequestContext::new(
+            req.clone(),
+            extract_headers(req),
+            req_type.clone(),
+            req.get_except_once_props()?,
+            self.props_cache.clone(),
+        );
+
+        // props are renamed before anything else, so that the keys requested by partial
+        // reloads are matched against the renamed ones
+        let mut props = match &self.prop_keys_transform {
+            Some(transform) => transform.transform_props(props),
+            None => props,
+        };
+
+        // the shared props are cloned out of the extensions (which is cheap, as they are behind
+        // a `Rc`), so that the extensions aren't borrowed while the resolvers are awaited
+        let shared_props = req
+            .extensions()
+            .get::<SharedProps>()
+            .map(|SharedProps(shared_props)| shared_props.clone());
+
+        // the flashed data is pulled after the version check, so that it survives the reload
+        // triggered by an assets version mismatch
+        let flashed_props = match &self.prop_keys_transform {
+            _ if response_options.skips_flash() => HashMap::new(),
+            Some(transform) => transform.transform_props(pull_flashed_props(req)),
+            None => pull_flashed_props(req),
+        };
+
+        let mut shared_props: Option<HashMap<String, Cow<InertiaProp>>> =
+            shared_props.as_deref().map(|shared_props| {
+                let mut shared_props: HashMap<String, Cow<InertiaProp>> =
+                    match &self.prop_keys_transform {
+                        Some(transform) => transform.transform_borrowed_props(shared_props),
+                        None => shared_props
+                            .iter()
+                            .map(|(key, prop)| (key.clone(), Cow::Borrowed(prop)))
+                            .collect(),
+                    };
+
+                shared_props.extend(
+                    flashed_props
+                        .into_iter()
+                        .map(|(key, prop)| (key, Cow::Owned(prop))),
+                );
+
+                shared_props
+            });
+
+        // the colliding props that lose the merge are dropped before anything is resolved, so
+        // that neither their resolvers run nor their metadata is sent
+        let deep_merged_keys = match &mut shared_props {
+            Some(shared_props) => self
+                .props_merge_strategy
+                .settle_collisions(&mut props, shared_props)?,
+            None => Vec::new(),
+        };
+
+        let mut deferred_props = InertiaProp::resolve_deferred_props(&props, &req_type);
+        let mut mergeable_props = InertiaProp::resolve_mergeable_props(&props, &req_type, &reset);
+        let mut once_props = InertiaProp::resolve_once_props(&props, &req_type);
+        let mut scroll_props = InertiaProp::resolve_scroll_props(&props, &req_type, &reset);
+
+        if let Some(shared_props) = &shared_props {
+            // deep-merged props are described by their page prop's metadata
+            let metadata_props;
+            let metadata_props = match deep_merged_keys.is_empty() {
+                true => shared_props,
+                false => {
+                    metadata_props = shared_props
+                        .iter()
+                        .filter(|(key, _)| !deep_merged_keys.contains(key))
+                        .map(|(key, prop)| (key.clone(), Cow::Borrowed(prop.as_ref())))
+                        .collect::<HashMap<_, _>>();
+                    &metadata_props
+                }
+            };
+
+            for (group, keys) in InertiaProp::resolve_deferred_props(metadata_props, &req_type) {
+                deferred_props.entry(group).or_default().extend(keys);
+            }
 
-        if let Some(SharedProps(shared_props)) = req.extensions().get::<SharedProps>() {
-            let shared_props = InertiaProp::resolve_props(shared_props, req_type);
-            props.extend(shared_props);
+            mergeable_props.extend(InertiaProp::resolve_mergeable_props(
+                metadata_props,
+                &req_type,
+                &reset,
+            ));
+
+            once_props.extend(InertiaProp::resolve_once_props(metadata_props, &req_type));
+            scroll_props.extend(InertiaProp::resolve_scroll_props(
+                metadata_props,
+                &req_type,
+                &reset,
+            ));
         }
 
-        let page = InertiaPage::new(component, url, Some(self.version.to_string()), props);
+        // page and shared props are resolved together, so that the resolvers of both sets are
+        // awaited concurrently
+        let (mut props, shared_props) =
+            futures::try_join!(InertiaProp::resolve_props(&props, &ctx), async {
+                match &shared_props {
+                    Some(shared_props) => InertiaProp::resolve_props(shared_props, &ctx)
+                        .await
+                        .map(Some),
+                    None => Ok(None),
+                }
+            },)?;
+
+        if let Some(shared_props) = shared_props {
+            self.props_merge_strategy.merge(&mut props, shared_props)?;
+        }
+
+        let page = InertiaPage::new(component, url, Some(self.version.to_string()), props)
+            .with_deferred_props(deferred_props)
+            .with_mergeable_props(mergeable_props)
+            .with_once_props(once_props)
+            .with_scroll_props(scroll_props);
+
+        let history = req
+            .extensions()
+            .get::<HistoryOptions>()
+            .copied()
+            .unwrap_or_default();
+
+        let clear_history = history.clear
+            || req
+                .extensions()
+                .get::<InertiaTemporarySession>()
+                .is_some_and(|session| session.clear_history);
+
+        let page = page.with_history(
+            history.encrypt.unwrap_or(self.encrypt_history),
+            clear_history,
+        );
 
         // if it's an inertia request, returns an InertiaPage object
         if req.is_inertia_request() {
-            return Ok(page.respond_to(req));
+            let serialized_page = convert_struct_to_json_bytes(&page)?;
+            self.check_page_size(&page, Some(serialized_page.len()))?;
+
+            let mut response = page_response(serialized_page);
+
+            // prefetched pages are cached by the client-side adapter, and must not be served
+            // by shared caches nor in place of a real visit
+            if req.is_prefetch_request() {
+                let headers = response.headers_mut();
+                headers.insert(CACHE_CONTROL, HeaderValue::from_static("private"));
+                headers.append(VARY, HeaderValue::from_static(inertia::PURPOSE));
+            }
+
+            response_options.apply(&mut response);
+            return Ok(response);
         }
 
+        self.check_page_size(&page, None)?;
+
         let mut ssr_page = None;
 
-        if self.ssr_url.is_some() {
-            match request_page_render(self.ssr_url.as_ref().unwrap(), page.clone()).await {
+        if let Some(ssr_url) = self.ssr_url.as_ref() {
+            match request_page_render(ssr_url, page.clone()).await {
                 Err(err) => {
                     log::warn!(
                         "{}",
@@ -111,24 +293,25 @@ where
             Ok(html) => html,
         };
 
-        return Ok(HttpResponseBuilder::new(StatusCode::OK)
+        let mut response = HttpResponseBuilder::new(StatusCode::OK)
             .insert_header(headers::InertiaHeader::Inertia.convert())
             .insert_header(actix_web::http::header::ContentType::html())
             .body(html)
-            .respond_to(req));
+            .respond_to(req);
+
+        response_options.apply(&mut response);
+        Ok(response)
+    }
+
+    fn back(&self, req: &HttpRequest) -> HttpResponse {
+        HttpResponseBuilder::new(redirect_status(req))
+            .append_header((LOCATION, self.back_url(req)))
+            .finish()
     }
 
     #[inline]
     fn location(req: &HttpRequest, url: &str) -> HttpResponse {
-        if !req.is_inertia_request() {
-            return HttpResponse::Found()
-                .append_header((actix_web::http::header::LOCATION, url))
-                .finish();
-        }
-
-        HttpResponseBuilder::new(StatusCode::CONFLICT)
-            .append_header(headers::InertiaHeader::InertiaLocation(url).convert())
-            .finish()
+        location_response(req, url)
     }
 }
 
@@ -139,7 +322,7 @@ impl ResponseError for InertiaError {
 
     fn error_response(&self) -> HttpResponse<BoxBody> {
         HttpResponseBuilder::new(StatusCode::INTERNAL_SERVER_ERROR)
-            .insert_header(actix_web::http::header::ContentType::json())
+            .insert_header(actix_web::http::header::ContentType::plaintext())
             .body(self.get_cause())
     }
 }
@@ -174,6 +357,13 @@ impl InertiaHttpRequest for HttpRequest {
         }
     }
 
+    fn is_prefetch_request(&self) -> bool {
+        self.headers()
+            .get(headers::PURPOSE)
+            .and_then(|purpose| purpose.to_str().ok())
+            .is_some_and(|purpose| purpose.eq_ignore_ascii_case(PREFETCH_PURPOSE))
+    }
+
     fn get_request_type(&self) -> Result<InertiaRequestType, InertiaError> {
         let partial_comp = self.headers().get(headers::X_INERTIA_PARTIAL_COMPONENT);
 
@@ -194,22 +384,40 @@ impl InertiaHttpRequest for HttpRequest {
         let only = extract_partials_headers_content(self, &headers::X_INERTIA_PARTIAL_DATA)?;
         let except = extract_partials_headers_content(self, &headers::X_INERTIA_PARTIAL_EXCEPT)?;
 
+        let scroll_merge_intent = match self
+            .headers()
+            .get(headers::X_INERTIA_INFINITE_SCROLL_MERGE_INTENT)
+            .and_then(|intent| intent.to_str().ok())
+        {
+            Some("prepend") => ScrollMergeIntent::Prepend,
+            _ => ScrollMergeIntent::Append,
+        };
+
         let partials = PartialComponent {
             component,
             only,
             except,
+            scroll_merge_intent,
         };
 
         Ok(InertiaRequestType::Partial(partials))
     }
 
+    fn get_except_once_props(&self) -> Result<Vec<String>, InertiaError> {
+        extract_partials_headers_content(self, &headers::X_INERTIA_EXCEPT_ONCE_PROPS)
+    }
+
+    fn get_reset_props(&self) -> Result<Vec<String>, InertiaError> {
+        extract_partials_headers_content(self, &headers::X_INERTIA_RESET)
+    }
+
     /// Checks if application assets version matches.
     /// If the request contains the inertia version header, it will be checked.
     /// Otherwise, it means it does not have outdated assets and can also pass.
     fn check_inertia_version(&self, current_version: &str) -> bool {
         self.headers()
             .get(headers::X_INERTIA_VERSION)
-            .map_or(true, |version| {
+            .is_none_or(|version| {
                 version
                     .to_str()
                     .is_ok_and(|version| version == current_version)
@@ -217,6 +425,25 @@ impl InertiaHttpRequest for HttpRequest {
     }
 }
 
+/// Collects the request headers. Repeated headers are joined with `", "` into a single value.
+fn extract_headers(req: &HttpRequest) -> HashMap<String, String> {
+    let mut headers = HashMap::<String, String>::new();
+
+    for (name, value) in req.headers().iter() {
+        if let Ok(value) = value.to_str() {
+            headers
+                .entry(name.to_string())
+                .and_modify(|values| {
+                    values.push_str(", ");
+                    values.push_str(value);
+                })
+                .or_insert_with(|| value.to_string());
+        }
+    }
+
+    headers
+}
+
 fn extract_partials_headers_content(
     req: &HttpRequest,
     header_name: &HeaderName,
@@ -239,6 +466,9 @@ fn extract_partials_headers_content(
 
 pub trait InertiaActixHelpers {
     fn check_and_handle_version_mismatch(&self, req: &HttpRequest) -> Result<(), HttpResponse>;
+
+    /// Gets the URL of the previous page. See [`InertiaResponder::back`].
+    fn back_url(&self, req: &HttpRequest) -> String;
 }
 
 impl<T> InertiaActixHelpers for Inertia<T>
@@ -264,6 +494,45 @@ where
 
         Ok(())
     }
+
+    fn back_url(&self, req: &HttpRequest) -> String {
+        req.extensions()
+            .get::<InertiaTemporarySession>()
+            .map(|session| session.prev_req_url.clone())
+            .filter(|url| !url.is_empty())
+            .or_else(|| {
+                req.headers()
+                    .get(REFERER)
+                    .and_then(|referer| referer.to_str().ok())
+                    .map(ToString::to_string)
+            })
+            .unwrap_or_else(|| self.default_back_url.to_string())
+    }
+}
+
+/// Inertia requests are redirected with a `409 Conflict` and the `X-Inertia-Location` header,
+/// so that the client-side adapter performs a full page visit. Other requests get a plain
+/// `302 Found` redirect.
+pub(crate) fn location_response(req: &HttpRequest, url: &str) -> HttpResponse {
+    if !req.is_inertia_request() {
+        return HttpResponse::Found()
+            .append_header((actix_web::http::header::LOCATION, url))
+            .finish();
+    }
+
+    HttpResponseBuilder::new(StatusCode::CONFLICT)
+        .append_header(headers::InertiaHeader::InertiaLocation(url).convert())
+        .finish()
+}
+
+/// Non-GET requests must be redirected with `303 See Other`, so that the client follows the
+/// redirect with a GET request.
+pub(crate) fn redirect_status(req: &HttpRequest) -> StatusCode {
+    if req.method() == Method::GET {
+        StatusCode::FOUND
+    } else {
+        StatusCode::SEE_OTHER
+    }
 }
 
 impl FromRequest for InertiaTemporarySession {
@@ -287,10 +556,11 @@ mod test {
     use crate::inertia::{InertiaHttpRequest, InertiaResponder, ViewData};
     use crate::props::InertiaProp;
     use crate::providers::actix::headers::{
-        InertiaHeader, X_INERTIA_PARTIAL_COMPONENT, X_INERTIA_PARTIAL_DATA,
-        X_INERTIA_PARTIAL_EXCEPT,
+        InertiaHeader, X_INERTIA_INFINITE_SCROLL_MERGE_INTENT, X_INERTIA_PARTIAL_COMPONENT,
+        X_INERTIA_PARTIAL_DATA, X_INERTIA_PARTIAL_EXCEPT, X_INERTIA_RESET,
     };
-    use crate::req_type::PartialComponent;
+    use crate::req_type::{PartialComponent, ScrollMergeIntent};
+    use crate::request_context::InertiaRequestContext;
     use crate::{
         Component, Inertia, InertiaError, InertiaPage, InertiaVersion, TemplateResolverOutput,
     };
@@ -307,6 +577,7 @@ mod test {
         request = request.insert_header((X_INERTIA_PARTIAL_DATA, "events,popularUsers")); // not any props but events and popularUsers
 
         request = request.insert_header((X_INERTIA_PARTIAL_EXCEPT, "auth")); // all props but auth
+        request = request.insert_header((X_INERTIA_INFINITE_SCROLL_MERGE_INTENT, "prepend"));
         let request = request.to_http_request();
 
         let partials = request.get_request_type().unwrap();
@@ -316,11 +587,38 @@ mod test {
             PartialComponent {
                 only: vec!["events".to_string(), "popularUsers".to_string()],
                 except: vec!["auth".to_string()],
+                scroll_merge_intent: ScrollMergeIntent::Prepend,
                 component: Component("/Index".to_string())
             }
         )
     }
 
+    #[test]
+    async fn test_extract_repeated_headers() {
+        let request = test::TestRequest::default()
+            .append_header(("Accept-Language", "en"))
+            .append_header(("Accept-Language", "pt-BR"))
+            .to_http_request();
+
+        let headers = super::extract_headers(&request);
+        assert_eq!("en, pt-BR", headers["accept-language"]);
+    }
+
+    #[test]
+    async fn test_get_reset_props() {
+        // standard visits may ask for props to be reset too
+        let request = test::TestRequest::default()
+            .insert_header(InertiaHeader::Inertia.convert())
+            .insert_header((X_INERTIA_RESET, "events,posts")) // replace them rather than merging
+            .to_http_request();
+
+        assert!(request.get_request_type().unwrap().is_standard());
+        assert_eq!(
+            vec!["events".to_string(), "posts".to_string()],
+            request.get_reset_props().unwrap()
+        );
+    }
+
     #[test]
     async fn test_inertia_page() {
         async fn resolver(
@@ -381,7 +679,15 @@ mod test {
             Component("/Users/Index".into()),
             "/users".to_string(),
             Some("gen_the_version".to_string()),
-            InertiaProp::resolve_props(&props, fake_req.get_request_type().unwrap()),
+            InertiaProp::resolve_props(
+                &props,
+                &InertiaRequestContext::from_request_type(
+                    fake_req.get_request_type().unwrap(),
+                    vec![],
+                ),
+            )
+            .await
+            .unwrap(),
         );
 
         let body = inertia
@@ -395,4 +701,63 @@ mod test {
             serde_json::to_string(&json!(page)).unwrap(),
         );
     }
+
+    #[test]
+    async fn test_page_hard_size_limit() {
+        fn resolver(
+            _path: &'static str,
+            _view_data: ViewData,
+            _data: &(),
+        ) -> TemplateResolverOutput {
+            Box::pin(async move { Ok(String::new()) })
+        }
+
+        let inertia = Inertia::new(
+            InertiaConfig::builder()
+                .set_url("https://my-inertia-website.com")
+                .set_version(InertiaVersion::Literal("v1"))
+                .set_template_path("/resources/view/template.hbs")
+                .set_template_resolver(&resolver)
+                .set_template_resolver_data(&())
+                .set_page_hard_size_limit(256)
+                .build(),
+        )
+        .unwrap();
+
+        let mut props = HashMap::<String, InertiaProp>::new();
+        props.insert("title".into(), InertiaProp::Data("Users".into()));
+
+        for with_inertia_header in [true, false] {
+            let mut req = test::TestRequest::get().uri("/users");
+
+            if with_inertia_header {
+                req = req.insert_header(InertiaHeader::Inertia.convert());
+            }
+
+            let response = inertia
+                .render_with_props(&req.to_http_request(), "Users".into(), props.clone())
+                .await;
+
+            assert!(response.is_ok());
+        }
+
+        props.insert(
+            "users".into(),
+            InertiaProp::Data(json!(vec!["John Doe"; 32])),
+        );
+
+        for with_inertia_header in [true, false] {
+            let mut req = test::TestRequest::get().uri("/users");
+
+            if with_inertia_header {
+                req = req.insert_header(InertiaHeader::Inertia.convert());
+            }
+
+            let response = inertia
+                .render_with_props(&req.to_http_request(), "Users".into(), props.clone())
+                .await;
+
+            assert!(matches!(response, Err(InertiaError::PageSizeError(_))));
+        }
+    }
 }
@@ -1,20 +1,27 @@
 use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::HeaderMap;
 use actix_web::http::{Method, StatusCode};
 use actix_web::Error;
-use actix_web::HttpMessage;
+use actix_web::{HttpMessage, HttpRequest};
 use futures_util::future::LocalBoxFuture;
-use serde_json::to_value;
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::future::{ready, Ready};
+use std::rc::Rc;
 use std::sync::Arc;
 
+use super::headers;
+use super::redirect::FlashStoreHandle;
+use crate::inertia::InertiaHttpRequest;
 use crate::temporary_session::InertiaTemporarySession;
-use crate::{InertiaProp, InertiaProps};
+use crate::utils::inertia_err_msg;
+use crate::{FlashStore, InertiaProp, InertiaProps};
 
 type SharedPropsCallback = dyn Fn(&ServiceRequest) -> InertiaProps;
 
 pub struct InertiaMiddleware {
     shared_props_cb: Arc<SharedPropsCallback>,
+    flash_store: Option<FlashStoreHandle>,
 }
 
 impl Default for InertiaMiddleware {
@@ -27,6 +34,7 @@ impl InertiaMiddleware {
     pub fn new() -> Self {
         Self {
             shared_props_cb: Arc::new(|_req| HashMap::new()),
+            flash_store: None,
         }
     }
 
@@ -34,6 +42,17 @@ impl InertiaMiddleware {
         self.shared_props_cb = props;
         self
     }
+
+    /// Sets the store that keeps the data flashed by [`InertiaRedirect`]s. The flashed errors
+    /// are merged into the next page's `errors` prop, and the other flashed data is sent as its
+    /// `flash` prop. The data is only pulled from the store when a page is rendered for a
+    /// request that isn't a prefetch.
+    ///
+    /// [`InertiaRedirect`]: crate::actix::InertiaRedirect
+    pub fn with_flash_store(mut self, store: Arc<dyn FlashStore<HttpRequest>>) -> Self {
+        self.flash_store = Some(FlashStoreHandle(store));
+        self
+    }
 }
 
 // Middleware factory is `Transform` trait
@@ -56,6 +75,7 @@ where
         ready(Ok(InertiaMiddlewareService {
             service,
             shared_props: shpcb,
+            flash_store: self.flash_store.clone(),
         }))
     }
 }
@@ -63,9 +83,13 @@ where
 pub struct InertiaMiddlewareService<S> {
     service: S,
     shared_props: Arc<SharedPropsCallback>,
+    flash_store: Option<FlashStoreHandle>,
 }
 
-pub(crate) struct SharedProps(pub InertiaProps);
+pub(crate) struct SharedProps(pub Rc<InertiaProps>);
+
+/// The unscoped errors of the `errors` shared prop, which the flashed errors are merged into.
+struct SharedErrors(Map<String, Value>);
 
 impl<S, B> Service<ServiceRequest> for InertiaMiddlewareService<S>
 where
@@ -82,12 +106,33 @@ where
     fn call(&self, req: ServiceRequest) -> Self::Future {
         let mut shared_props = (self.shared_props)(&req);
 
-        if let Some(request_props) = req.extensions().get::<InertiaTemporarySession>() {
-            let errors = to_value(&request_props.errors).unwrap();
-            shared_props.insert("errors".into(), InertiaProp::Always(errors));
+        let session_errors = session_errors(req.request());
+
+        // an `errors` prop given by the shared props callback is kept, and the session's errors
+        // are merged into it if it's an object
+        let mut errors = match shared_props.get("errors") {
+            Some(InertiaProp::Data(Value::Object(errors)))
+            | Some(InertiaProp::Always(Value::Object(errors))) => errors.clone(),
+            _ => Map::new(),
+        };
+
+        if !session_errors.is_empty() || !shared_props.contains_key("errors") {
+            errors.extend(session_errors);
+            shared_props.insert(
+                "errors".into(),
+                InertiaProp::Always(scope_errors(req.headers(), errors.clone())),
+            );
+        }
+
+        // the flashed data is only pulled when a page is rendered, so that it isn't lost by
+        // requests that don't render one
+        if let Some(flash_store) = &self.flash_store {
+            req.extensions_mut().insert(flash_store.clone());
+            req.extensions_mut().insert(SharedErrors(errors));
         }
 
-        req.extensions_mut().insert(SharedProps(shared_props));
+        req.extensions_mut()
+            .insert(SharedProps(Rc::new(shared_props)));
 
         let fut: <S as Service<ServiceRequest>>::Future = self.service.call(req);
 
@@ -108,3 +153,84 @@ where
         })
     }
 }
+
+/// Pulls the data flashed to the client, if the `InertiaMiddleware` has a flash store, and
+/// returns it as the props that override the shared ones: the flashed errors are merged into
+/// the `errors` prop, and the other data is sent as the `flash` prop. Prefetch requests don't
+/// pull anything, so that the flashed data is kept for the actual visit.
+pub(crate) fn pull_flashed_props(req: &HttpRequest) -> InertiaProps {
+    let mut props = HashMap::new();
+
+    if req.is_prefetch_request() {
+        return props;
+    }
+
+    let Some(FlashStoreHandle(store)) = req.extensions().get::<FlashStoreHandle>().cloned() else {
+        return props;
+    };
+
+    let data = match store.pull(req) {
+        Ok(Some(data)) => data,
+        Ok(None) => return props,
+        Err(err) => {
+            log::warn!(
+                "{}",
+                inertia_err_msg(format!("Failed to pull the flashed data. {}", err))
+            );
+            return props;
+        }
+    };
+
+    if !data.errors.is_empty() {
+        let mut errors = req
+            .extensions()
+            .get::<SharedErrors>()
+            .map(|SharedErrors(errors)| errors.clone())
+            .unwrap_or_default();
+        errors.extend(data.errors);
+
+        props.insert(
+            "errors".into(),
+            InertiaProp::Always(scope_errors(req.headers(), errors)),
+        );
+    }
+
+    if !data.flash.is_empty() {
+        props.insert(
+            "flash".into(),
+            InertiaProp::Always(Value::Object(data.flash)),
+        );
+    }
+
+    props
+}
+
+fn session_errors(req: &HttpRequest) -> Map<String, Value> {
+    req.extensions()
+        .get::<InertiaTemporarySession>()
+        .and_then(|request_props| request_props.errors.clone())
+        .unwrap_or_default()
+}
+
+/// Nests the errors under the error bag requested by the client through the
+/// `X-Inertia-Error-Bag` header, so that different forms in the same page don't overwrite each
+/// other's errors. If there are no errors, an empty object is returned.
+fn scope_errors(headers: &HeaderMap, errors: Map<String, Value>) -> Value {
+    if errors.is_empty() {
+        return Value::Object(errors);
+    }
+
+    let error_bag = headers
+        .get(headers::X_INERTIA_ERROR_BAG)
+        .and_then(|error_bag| error_bag.to_str().ok())
+        .filter(|error_bag| !error_bag.is_empty());
+
+    match error_bag {
+        None => Value::Object(errors),
+        Some(error_bag) => {
+            let mut scoped_errors = Map::new();
+            scoped_errors.insert(error_bag.to_string(), Value::Object(errors));
+            Value::Object(scoped_errors)
+        }
+    }
+}
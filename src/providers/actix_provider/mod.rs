@@ -1,4 +1,7 @@
+pub mod error_handlers;
 pub mod facade;
 pub mod headers;
 pub mod impls;
 pub mod middleware;
+pub mod redirect;
+pub mod response;
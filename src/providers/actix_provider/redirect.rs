@@ -0,0 +1,159 @@
+use super::impls::{location_response, redirect_status};
+use crate::utils::inertia_err_msg;
+use crate::{FlashData, FlashStore, InertiaError};
+use actix_web::body::BoxBody;
+use actix_web::http::header::LOCATION;
+use actix_web::{
+    HttpMessage, HttpRequest, HttpResponse, HttpResponseBuilder, Responder, ResponseError,
+};
+use reqwest::Url;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+/// The flash store given to the `InertiaMiddleware`, kept in the request extensions so that
+/// redirects can write into it.
+#[derive(Clone)]
+pub(crate) struct FlashStoreHandle(pub Arc<dyn FlashStore<HttpRequest>>);
+
+/// A redirect that flashes errors and other data to the next request, through the flash store
+/// given to [`InertiaMiddleware::with_flash_store`]. Non-GET requests are redirected with a
+/// `303 See Other` status.
+///
+/// # Examples
+/// ```rust
+/// use inertia_rust::actix::InertiaRedirect;
+/// use serde_json::{json, Map};
+///
+/// let mut errors = Map::new();
+/// errors.insert("email".into(), json!("This email is already taken."));
+///
+/// let redirect = InertiaRedirect::to("/signup")
+///     .with_errors(errors)
+///     .with_flash("warning", "Please, review your data.");
+/// ```
+///
+/// [`InertiaMiddleware::with_flash_store`]: crate::actix::InertiaMiddleware::with_flash_store
+pub struct InertiaRedirect {
+    url: String,
+    data: FlashData,
+}
+
+impl InertiaRedirect {
+    pub fn to(url: impl ToString) -> Self {
+        Self {
+            url: url.to_string(),
+            data: FlashData::default(),
+        }
+    }
+
+    /// Flashes validation errors, merged into the `errors` prop of the next page.
+    pub fn with_errors(mut self, errors: Map<String, Value>) -> Self {
+        self.data.errors.extend(errors);
+        self
+    }
+
+    /// Flashes a value under `key` in the `flash` prop of the next page.
+    pub fn with_flash(mut self, key: impl ToString, value: impl Into<Value>) -> Self {
+        self.data.flash.insert(key.to_string(), value.into());
+        self
+    }
+}
+
+impl Responder for InertiaRedirect {
+    type Body = BoxBody;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        if !self.data.is_empty() {
+            let store = req.extensions().get::<FlashStoreHandle>().cloned();
+
+            match store {
+                None => log::warn!(
+                    "{}",
+                    inertia_err_msg(
+                        "There is no flash store in the InertiaMiddleware, so the redirect data has been lost.".into()
+                    )
+                ),
+                Some(FlashStoreHandle(store)) => {
+                    if let Err(err) = store.put(req, self.data) {
+                        return err.error_response();
+                    }
+                }
+            }
+        }
+
+        HttpResponseBuilder::new(redirect_status(req))
+            .append_header((LOCATION, self.url))
+            .finish()
+    }
+}
+
+/// A redirect to an external URL, or to a page that isn't handled by Inertia. Inertia requests
+/// get a `409 Conflict` with the `X-Inertia-Location` header, so that the client-side adapter
+/// performs a full page visit, while other requests get a plain `302 Found` redirect.
+///
+/// # Examples
+/// ```rust
+/// use inertia_rust::actix::ExternalRedirect;
+///
+/// let redirect = ExternalRedirect::to("https://github.com/login/oauth/authorize");
+/// assert!(redirect.is_ok());
+///
+/// let redirect = ExternalRedirect::to("javascript:alert(1)");
+/// assert!(redirect.is_err());
+/// ```
+#[derive(Debug)]
+pub struct ExternalRedirect {
+    url: Url,
+}
+
+impl ExternalRedirect {
+    /// Creates a redirect to an absolute `http` or `https` URL.
+    ///
+    /// # Errors
+    /// Returns an [`InertiaError::UrlError`] if the URL is relative or invalid.
+    pub fn to(url: &str) -> Result<Self, InertiaError> {
+        let url = Url::parse(url)
+            .map_err(|err| InertiaError::UrlError(format!("Invalid URL \"{}\": {}", url, err)))?;
+
+        Self::from_url(url)
+    }
+
+    /// Creates a redirect to `url`, which is resolved against `base_url` if it's relative.
+    ///
+    /// # Errors
+    /// Returns an [`InertiaError::UrlError`] if any of the URLs is invalid.
+    pub(crate) fn resolve(base_url: &str, url: &str) -> Result<Self, InertiaError> {
+        let base_url = Url::parse(base_url).map_err(|err| {
+            InertiaError::UrlError(format!("Invalid Inertia URL \"{}\": {}", base_url, err))
+        })?;
+
+        let url = base_url
+            .join(url)
+            .map_err(|err| InertiaError::UrlError(format!("Invalid URL \"{}\": {}", url, err)))?;
+
+        Self::from_url(url)
+    }
+
+    fn from_url(url: Url) -> Result<Self, InertiaError> {
+        if !["http", "https"].contains(&url.scheme()) {
+            return Err(InertiaError::UrlError(format!(
+                "Cannot redirect to \"{}\", as only http and https URLs are allowed.",
+                url
+            )));
+        }
+
+        Ok(Self { url })
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+}
+
+impl Responder for ExternalRedirect {
+    type Body = BoxBody;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        location_response(req, self.url.as_str())
+    }
+}
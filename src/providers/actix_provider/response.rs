@@ -0,0 +1,162 @@
+use super::facade::extract_inertia;
+use crate::inertia::InertiaResponder;
+use crate::{Component, Inertia, InertiaError, InertiaProps};
+use actix_web::cookie::Cookie;
+use actix_web::http::header::{HeaderName, HeaderValue, TryIntoHeaderPair, SET_COOKIE};
+use actix_web::http::StatusCode;
+use actix_web::{HttpMessage, HttpRequest, HttpResponse};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// The status and the extra headers of a rendered page's response, stored in the request
+/// extensions until the page is rendered.
+#[derive(Clone, Default)]
+pub(crate) struct ResponseOptions {
+    status: Option<StatusCode>,
+    headers: Vec<HeaderAction>,
+    skip_flash: bool,
+}
+
+#[derive(Clone)]
+enum HeaderAction {
+    Insert(HeaderName, HeaderValue),
+    Append(HeaderName, HeaderValue),
+}
+
+impl ResponseOptions {
+    /// Whether the page must be rendered without pulling the data flashed to the client.
+    pub fn skips_flash(&self) -> bool {
+        self.skip_flash
+    }
+
+    pub fn apply(self, response: &mut HttpResponse) {
+        if let Some(status) = self.status {
+            *response.status_mut() = status;
+        }
+
+        for action in self.headers {
+            match action {
+                HeaderAction::Insert(name, value) => {
+                    response.headers_mut().insert(name, value);
+                }
+                HeaderAction::Append(name, value) => {
+                    response.headers_mut().append(name, value);
+                }
+            }
+        }
+    }
+}
+
+/// Renders an Inertia page with a custom HTTP status and extra headers, on both Inertia (JSON)
+/// and full (HTML) responses. Created by [`response`].
+///
+/// [`response`]: crate::actix::response
+pub struct InertiaResponseBuilder<'a, T>
+where
+    T: 'static,
+{
+    req: &'a HttpRequest,
+    component: Component,
+    props: InertiaProps,
+    options: ResponseOptions,
+    header_error: Option<InertiaError>,
+    _template_resolver_data: PhantomData<T>,
+}
+
+impl<'a, T> InertiaResponseBuilder<'a, T>
+where
+    T: 'static,
+{
+    pub(crate) fn new(req: &'a HttpRequest, component: Component) -> Self {
+        Self {
+            req,
+            component,
+            props: HashMap::new(),
+            options: ResponseOptions::default(),
+            header_error: None,
+            _template_resolver_data: PhantomData,
+        }
+    }
+
+    /// Leaves the flashed data in the flash store, for the page rendered after this one.
+    pub(crate) fn without_flash(mut self) -> Self {
+        self.options.skip_flash = true;
+        self
+    }
+
+    pub fn with_props(mut self, props: InertiaProps) -> Self {
+        self.props = props;
+        self
+    }
+
+    /// Sets the response status. Defaults to `200 OK`.
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.options.status = Some(status);
+        self
+    }
+
+    /// Inserts a header in the response, replacing any existing values of the same header. If the
+    /// header is invalid, rendering fails with an [`InertiaError::HeaderError`].
+    pub fn insert_header(self, header: impl TryIntoHeaderPair) -> Self {
+        self.push_header(header, HeaderAction::Insert)
+    }
+
+    /// Appends a header to the response, keeping any existing values of the same header. If the
+    /// header is invalid, rendering fails with an [`InertiaError::HeaderError`].
+    pub fn append_header(self, header: impl TryIntoHeaderPair) -> Self {
+        self.push_header(header, HeaderAction::Append)
+    }
+
+    /// Adds a `Set-Cookie` header to the response.
+    pub fn cookie(mut self, cookie: Cookie<'_>) -> Self {
+        match HeaderValue::from_str(&cookie.to_string()) {
+            Ok(value) => self
+                .options
+                .headers
+                .push(HeaderAction::Append(SET_COOKIE, value)),
+            Err(err) => {
+                self.header_error = Some(InertiaError::HeaderError(format!(
+                    "Invalid cookie: {}",
+                    err
+                )));
+            }
+        }
+
+        self
+    }
+
+    fn push_header(
+        mut self,
+        header: impl TryIntoHeaderPair,
+        action: fn(HeaderName, HeaderValue) -> HeaderAction,
+    ) -> Self {
+        match header.try_into_pair() {
+            Ok((name, value)) => self.options.headers.push(action(name, value)),
+            Err(err) => {
+                self.header_error = Some(InertiaError::HeaderError(format!(
+                    "Invalid response header: {}",
+                    err.into()
+                )));
+            }
+        }
+
+        self
+    }
+
+    /// Renders the page. Redirects caused by an assets version mismatch keep their own status.
+    ///
+    /// # Panic
+    /// Panics if Inertia instance hasn't been configured (set to AppData).
+    pub async fn render(self) -> Result<HttpResponse, InertiaError> {
+        if let Some(err) = self.header_error {
+            return Err(err);
+        }
+
+        let inertia: &Inertia<T> = extract_inertia(self.req);
+
+        self.req.extensions_mut().insert(self.options);
+        inertia
+            .render_with_props(self.req, self.component, self.props)
+            .await
+    }
+}
@@ -3,5 +3,7 @@ mod actix_provider;
 
 #[cfg(feature = "actix")]
 pub mod actix {
-    pub use super::actix_provider::{facade, headers, middleware};
+    pub use super::actix_provider::{
+        error_handlers, facade, headers, middleware, redirect, response,
+    };
 }
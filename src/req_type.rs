@@ -5,6 +5,15 @@ pub(crate) struct PartialComponent {
     pub component: Component,
     pub only: Vec<String>,
     pub except: Vec<String>,
+    /// Whether the page loaded by an infinite scroll goes after or before the loaded ones.
+    pub scroll_merge_intent: ScrollMergeIntent,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub(crate) enum ScrollMergeIntent {
+    #[default]
+    Append,
+    Prepend,
 }
 
 #[derive(Clone)]
@@ -27,6 +36,7 @@ impl InertiaRequestType {
     }
 
     #[inline]
+    #[allow(unused)]
     pub fn unwrap_partial(self) -> PartialComponent {
         match self {
             InertiaRequestType::Standard => {
@@ -36,6 +46,19 @@ impl InertiaRequestType {
         }
     }
 
+    /// Partial reloads only apply to the component they have been requested for. If the
+    /// rendered component is another one (e.g., the request got redirected to another page),
+    /// it must be treated as a standard visit.
+    #[inline]
+    pub fn for_component(self, component: &Component) -> Self {
+        match self {
+            InertiaRequestType::Partial(partials) if &partials.component != component => {
+                InertiaRequestType::Standard
+            }
+            req_type => req_type,
+        }
+    }
+
     #[inline]
     #[allow(unused)]
     pub fn partials(&self) -> Option<&PartialComponent> {
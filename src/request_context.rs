@@ -0,0 +1,130 @@
+use crate::inertia;
+#[cfg(test)]
+use crate::props_cache::InMemoryPropsCache;
+use crate::props_cache::PropsCache;
+use crate::req_type::InertiaRequestType;
+use std::any::Any;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+struct RequestContextInner {
+    request: Box<dyn Any>,
+    headers: HashMap<String, String>,
+    req_type: InertiaRequestType,
+    except_once_props: Vec<String>,
+    props_cache: Arc<dyn PropsCache>,
+}
+
+/// A framework-neutral view of the request being rendered, given to the request-aware
+/// [`PropResolver`]s when their props are resolved.
+///
+/// Cloning it is cheap, as its contents are reference-counted.
+///
+/// [`PropResolver`]: crate::PropResolver
+#[derive(Clone)]
+pub struct InertiaRequestContext(Rc<RequestContextInner>);
+
+impl InertiaRequestContext {
+    pub(crate) fn new<R>(
+        request: R,
+        headers: HashMap<String, String>,
+        req_type: InertiaRequestType,
+        except_once_props: Vec<String>,
+        props_cache: Arc<dyn PropsCache>,
+    ) -> Self
+    where
+        R: 'static,
+    {
+        Self(Rc::new(RequestContextInner {
+            request: Box::new(request),
+            headers,
+            req_type,
+            except_once_props,
+            props_cache,
+        }))
+    }
+
+    /// Gets the HTTP request of the framework opted by the cargo feature. E.g., an
+    /// `actix_web::HttpRequest`, from which the request extensions can be read.
+    ///
+    /// Returns `None` if `R` is not the framework's request type.
+    pub fn request<R>(&self) -> Option<&R>
+    where
+        R: 'static,
+    {
+        self.0.request.downcast_ref::<R>()
+    }
+
+    /// Gets the value of a request header. Header names are case-insensitive, and the values of
+    /// a repeated header are joined with `", "`.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.0
+            .headers
+            .get(&name.to_lowercase())
+            .map(|value| value.as_str())
+    }
+
+    /// Whether the client-side adapter is prefetching the page rather than visiting it. Side
+    /// effects (e.g., marking notifications as read) should be skipped on prefetches.
+    pub fn is_prefetch(&self) -> bool {
+        self.header(inertia::PURPOSE)
+            .is_some_and(|purpose| purpose.eq_ignore_ascii_case(inertia::PREFETCH_PURPOSE))
+    }
+
+    pub fn is_partial_reload(&self) -> bool {
+        self.0.req_type.is_partial()
+    }
+
+    /// The component whose props are being partially reloaded, if this is a partial reload.
+    pub fn partial_component(&self) -> Option<&str> {
+        self.0
+            .req_type
+            .partials()
+            .map(|partials| partials.component.0.as_str())
+    }
+
+    /// The keys of the props requested by a partial reload. It's empty if this isn't a
+    /// partial reload or if every prop has been requested.
+    pub fn partial_data(&self) -> &[String] {
+        self.0
+            .req_type
+            .partials()
+            .map_or(&[], |partials| partials.only.as_slice())
+    }
+
+    /// The keys of the props excluded by a partial reload.
+    pub fn partial_except(&self) -> &[String] {
+        self.0
+            .req_type
+            .partials()
+            .map_or(&[], |partials| partials.except.as_slice())
+    }
+
+    /// Creates a context without any framework request, for testing the props resolution.
+    #[cfg(test)]
+    pub(crate) fn from_request_type(
+        req_type: InertiaRequestType,
+        except_once_props: Vec<String>,
+    ) -> Self {
+        Self::new(
+            (),
+            HashMap::new(),
+            req_type,
+            except_once_props,
+            Arc::new(InMemoryPropsCache::default()),
+        )
+    }
+
+    pub(crate) fn req_type(&self) -> &InertiaRequestType {
+        &self.0.req_type
+    }
+
+    pub(crate) fn except_once_props(&self) -> &[String] {
+        &self.0.except_once_props
+    }
+
+    pub(crate) fn props_cache(&self) -> &dyn PropsCache {
+        self.0.props_cache.as_ref()
+    }
+}
@@ -10,6 +10,9 @@ use serde_json::{Map, Value};
 pub struct InertiaTemporarySession {
     pub errors: Option<Map<String, Value>>,
     pub prev_req_url: String,
+    /// Whether the client-side history must be cleared on the next rendered page. E.g., it
+    /// should be flashed by a logout handler that redirects the user somewhere else.
+    pub clear_history: bool,
 }
 
 impl Default for InertiaTemporarySession {
@@ -17,6 +20,7 @@ impl Default for InertiaTemporarySession {
         InertiaTemporarySession {
             errors: None,
             prev_req_url: "/".to_string(),
+            clear_history: false,
         }
     }
 }
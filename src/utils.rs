@@ -35,18 +35,15 @@ where
     Ok(value_as_map)
 }
 
-pub(crate) fn convert_struct_to_stringified_json<T>(s: T) -> Result<String, InertiaError>
+/// Serializes the given struct straight into a JSON bytes buffer, without building any
+/// intermediate `Value`.
+pub(crate) fn convert_struct_to_json_bytes<T>(s: &T) -> Result<Vec<u8>, InertiaError>
 where
     T: Serialize,
 {
-    let map = convert_struct_to_map(s)?;
-    match serde_json::to_string(&map) {
-        Ok(json) => Ok(json),
-        Err(err) => Err(InertiaError::SerializationError(format!(
-            "Failed to serialize HashMap: {}",
-            err
-        ))),
-    }
+    serde_json::to_vec(s).map_err(|err| {
+        InertiaError::SerializationError(format!("Failed to serialize struct as JSON: {}", err))
+    })
 }
 
 pub(crate) async fn request_page_render(
@@ -117,4 +114,23 @@ mod test {
             "{\"foo\":{\"bar\":2024,\"baz\":true},\"statement\":\"Inertia slays!\"}"
         )
     }
+
+    #[test]
+    pub fn test_convert_struct_to_json_bytes() {
+        #[derive(serde::Serialize)]
+        struct Props {
+            statement: String,
+            years: Vec<u32>,
+        }
+
+        let props = Props {
+            statement: "Inertia slays!".into(),
+            years: vec![2024, 2025],
+        };
+
+        assert_eq!(
+            convert_struct_to_json_bytes(&props).unwrap(),
+            b"{\"statement\":\"Inertia slays!\",\"years\":[2024,2025]}"
+        )
+    }
 }
@@ -28,17 +28,16 @@ async fn _mocked_resolver(
 ) -> Result<String, InertiaError> {
     let path = Path::new(template_path);
 
-    let read_file = tokio::fs::read(&path).await;
-
-    if read_file.is_err() {
-        return Err(InertiaError::SsrError(format!(
-            "Failed to open root layout at {}: {:#}",
-            path.to_str().unwrap(),
-            read_file.unwrap_err()
-        )));
-    }
-
-    let data = read_file.unwrap();
+    let data = match tokio::fs::read(&path).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Err(InertiaError::SsrError(format!(
+                "Failed to open root layout at {}: {:#}",
+                path.to_str().unwrap(),
+                err
+            )))
+        }
+    };
 
     let mut html = match String::from_utf8(data) {
         Err(err) => {
@@ -12,14 +12,25 @@ use actix_web::{
 };
 use common::template_resolver::{get_dynamic_csr_expect, mocked_resolver};
 use inertia_rust::{
-    actix::{render, render_with_props, InertiaHeader, InertiaMiddleware},
+    actix::{
+        back, clear_history, encrypt_history, invalidate_cached_prop, is_prefetch, location,
+        redirect_back, render, render_with_props, render_with_struct, response,
+        InertiaErrorHandlers, InertiaHeader, InertiaMiddleware,
+    },
     InertiaPage, InertiaService, InertiaTemporarySession,
 };
-use inertia_rust::{Component, Inertia, InertiaConfig, InertiaProp, InertiaProps, InertiaVersion};
+use inertia_rust::{
+    Component, FlashData, FlashStore, Inertia, InertiaConfig, InertiaConfigBuilder, InertiaError,
+    InertiaProp, InertiaProps, InertiaVersion, PropResolver,
+};
 use serde_json::{json, Map};
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex, OnceLock},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::Duration,
 };
 
 const TEST_INERTIA_VERSION: &str = "v1.0.0";
@@ -62,6 +73,112 @@ async fn with_props(req: HttpRequest) -> impl Responder {
     }
 }
 
+#[get("/withstruct")]
+async fn with_struct(req: HttpRequest) -> impl Responder {
+    #[derive(serde::Serialize)]
+    struct Props {
+        user: &'static str,
+        roles: Vec<&'static str>,
+    }
+
+    let props = Props {
+        user: "John Doe",
+        roles: vec!["admin"],
+    };
+
+    render_with_struct::<(), _>(&req, Component("Index".into()), props).await
+}
+
+#[get("/invalidatecache")]
+async fn invalidate_cache(req: HttpRequest) -> impl Responder {
+    invalidate_cached_prop::<()>(&req, "visits");
+    render::<()>(&req, Component("Index".into())).await
+}
+
+#[get("/loggedout")]
+async fn logged_out(req: HttpRequest) -> impl Responder {
+    clear_history(&req);
+    render::<()>(&req, Component("Index".into())).await
+}
+
+#[get("/partials")]
+async fn partials(req: HttpRequest) -> impl Responder {
+    let mut props: InertiaProps = HashMap::new();
+    props.insert("foo".to_string(), InertiaProp::Data("foo".into()));
+    props.insert("bar".to_string(), InertiaProp::Data("bar".into()));
+    props.insert(
+        "baz".to_string(),
+        InertiaProp::Demand(PropResolver::new(|| "baz".into())),
+    );
+
+    render_with_props::<()>(&req, Component("Partials".into()), props).await
+}
+
+#[derive(Clone)]
+struct AuthenticatedUser(&'static str);
+
+#[get("/authenticated")]
+async fn authenticated(req: HttpRequest) -> impl Responder {
+    // pretends some authentication step has put the user in the extensions
+    req.extensions_mut().insert(AuthenticatedUser("John Doe"));
+    render::<()>(&req, Component("Index".into())).await
+}
+
+#[post("/back")]
+async fn post_back(req: HttpRequest) -> impl Responder {
+    back::<()>(&req)
+}
+
+#[post("/signup")]
+async fn signup(req: HttpRequest) -> impl Responder {
+    let mut errors = Map::new();
+    errors.insert("email".into(), "This email is already taken.".into());
+
+    redirect_back::<()>(&req)
+        .with_errors(errors)
+        .with_flash("warning", "Please, review your data.")
+}
+
+/// A flash store for a single client.
+#[derive(Default)]
+struct SingleClientFlashStore(Mutex<Option<FlashData>>);
+
+impl FlashStore<HttpRequest> for SingleClientFlashStore {
+    fn put(&self, _req: &HttpRequest, data: FlashData) -> Result<(), InertiaError> {
+        *self.0.lock().unwrap() = Some(data);
+        Ok(())
+    }
+
+    fn pull(&self, _req: &HttpRequest) -> Result<Option<FlashData>, InertiaError> {
+        Ok(self.0.lock().unwrap().take())
+    }
+}
+
+#[get("/legacy")]
+async fn legacy(req: HttpRequest) -> impl Responder {
+    location::<()>(&req, "/legacy/index.php")
+}
+
+#[get("/missing")]
+async fn missing(req: HttpRequest) -> impl Responder {
+    response::<()>(&req, Component("Errors/NotFound".into()))
+        .status(StatusCode::NOT_FOUND)
+        .insert_header(("Cache-Control", "no-store"))
+        .render()
+        .await
+}
+
+#[get("/failing")]
+async fn failing() -> Result<HttpResponse, InertiaError> {
+    Err(InertiaError::RenderError("Something went wrong.".into()))
+}
+
+#[get("/public")]
+async fn public(req: HttpRequest) -> impl Responder {
+    encrypt_history(&req, false);
+    render::<()>(&req, Component("Index".into())).await
+}
+
 #[put("/redirect")]
 async fn put_redirect() -> impl Responder {
     Redirect::to("/").using_status_code(StatusCode::MOVED_PERMANENTLY)
@@ -77,6 +194,39 @@ async fn delete_redirect() -> impl Responder {
     Redirect::to("/").using_status_code(StatusCode::FOUND)
 }
 
+fn generate_inertia() -> Inertia<()> {
+    generate_inertia_with(|config| config)
+}
+
+fn generate_inertia_with(
+    configure: impl FnOnce(
+        InertiaConfigBuilder<(), &'static str>,
+    ) -> InertiaConfigBuilder<(), &'static str>,
+) -> Inertia<()> {
+    let _ = SESSIONS_STORAGE.get_or_init(|| Arc::new(Mutex::new(Vec::new())));
+
+    let config = InertiaConfig::builder()
+        .set_url("https://inertiajs.com")
+        .set_version(InertiaVersion::Literal(TEST_INERTIA_VERSION))
+        .set_template_path("tests/common/root_layout.html")
+        .set_template_resolver(&mocked_resolver)
+        .set_template_resolver_data(&())
+        .set_reflash_fn(Box::new(move |session| {
+            if let Some(session) = session {
+                SESSIONS_STORAGE
+                    .get()
+                    .unwrap()
+                    .lock()
+                    .unwrap()
+                    .push(session);
+            }
+
+            Ok(())
+        }));
+
+    Inertia::new(configure(config).build()).unwrap()
+}
+
 async fn generate_actix_app() -> App<
     impl ServiceFactory<
         ServiceRequest,
@@ -86,35 +236,19 @@ async fn generate_actix_app() -> App<
         InitError = (),
     >,
 > {
-    let _ = SESSIONS_STORAGE.get_or_init(|| Arc::new(Mutex::new(Vec::new())));
-
-    let inertia = Inertia::new(
-        InertiaConfig::builder()
-            .set_url("https://inertiajs.com")
-            .set_version(InertiaVersion::Literal(TEST_INERTIA_VERSION))
-            .set_template_path("tests/common/root_layout.html")
-            .set_template_resolver(&mocked_resolver)
-            .set_template_resolver_data(&())
-            .set_reflash_fn(Box::new(move |session| {
-                if let Some(session) = session {
-                    SESSIONS_STORAGE
-                        .get()
-                        .unwrap()
-                        .lock()
-                        .unwrap()
-                        .push(session);
-                }
-
-                Ok(())
-            }))
-            .build(),
-    )
-    .unwrap();
-
     App::new()
-        .app_data(Data::new(inertia))
+        .app_data(Data::new(generate_inertia()))
         .service(home)
         .service(with_props)
+        .service(with_struct)
+        .service(logged_out)
+        .service(invalidate_cache)
+        .service(authenticated)
+        .service(partials)
+        .service(post_back)
+        .service(signup)
+        .service(legacy)
+        .service(missing)
         .service(put_redirect)
         .service(post_redirect)
         .service(delete_redirect)
@@ -210,6 +344,26 @@ async fn test_render_with_props() {
     );
 }
 
+#[tokio::test]
+async fn test_render_with_struct() {
+    let app = actix_web::test::init_service(generate_actix_app().await).await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/withstruct")
+        .insert_header(InertiaHeader::Inertia.convert())
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!(200u16, resp.status().as_u16());
+
+    let page: InertiaPage = actix_web::test::read_body_json(resp).await;
+    assert_eq!(
+        json!({"user": "John Doe", "roles": ["admin"]}),
+        json!(page.get_props())
+    );
+}
+
 #[tokio::test]
 async fn test_shared_props() {
     let test_shared_property_key = "sharedProperty";
@@ -249,6 +403,202 @@ async fn test_shared_props() {
     );
 }
 
+#[tokio::test]
+async fn test_page_props_override_shared_props() {
+    let app = actix_web::test::init_service(generate_actix_app().await.wrap(
+        InertiaMiddleware::new().with_shared_props(Arc::new(|_req| {
+            let mut shared_props = HashMap::new();
+            shared_props.insert("user".to_string(), InertiaProp::Always("Jane Doe".into()));
+            shared_props
+        })),
+    ))
+    .await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/withprops")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let page: InertiaPage =
+        actix_web::test::read_body_json(actix_web::test::call_service(&app, req).await).await;
+
+    assert_eq!("John Doe", page.get_props().get("user").unwrap());
+}
+
+#[tokio::test]
+async fn test_prefetch_request() {
+    let app = actix_web::test::init_service(generate_actix_app().await.wrap(
+        InertiaMiddleware::new().with_shared_props(Arc::new(|req| {
+            let mut shared_props = HashMap::new();
+            shared_props.insert(
+                "prefetched".to_string(),
+                InertiaProp::Always(is_prefetch(req.request()).into()),
+            );
+            shared_props
+        })),
+    ))
+    .await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .insert_header(("Purpose", "prefetch"))
+        .to_request();
+
+    let resp = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!("private", resp.headers().get("cache-control").unwrap());
+    assert_eq!("purpose", resp.headers().get("vary").unwrap());
+
+    let page: InertiaPage = actix_web::test::read_body_json(resp).await;
+    assert_eq!(true, page.get_props()["prefetched"]);
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let resp = actix_web::test::call_service(&app, req).await;
+
+    assert!(resp.headers().get("cache-control").is_none());
+
+    let page: InertiaPage = actix_web::test::read_body_json(resp).await;
+    assert_eq!(false, page.get_props()["prefetched"]);
+}
+
+#[tokio::test]
+async fn test_cached_shared_props() {
+    static VISITS: AtomicU32 = AtomicU32::new(0);
+
+    let app = actix_web::test::init_service(generate_actix_app().await.wrap(
+        InertiaMiddleware::new().with_shared_props(Arc::new(|_req| {
+            let mut shared_props = HashMap::new();
+            shared_props.insert(
+                "visits".to_string(),
+                InertiaProp::cached(
+                    "visits",
+                    Duration::from_secs(60),
+                    PropResolver::new(|| (VISITS.fetch_add(1, Ordering::SeqCst) + 1).into()),
+                ),
+            );
+            shared_props
+        })),
+    ))
+    .await;
+
+    let mut visits = Vec::new();
+
+    for uri in ["/", "/", "/invalidatecache", "/"] {
+        let req = actix_web::test::TestRequest::get()
+            .uri(uri)
+            .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+            .insert_header(InertiaHeader::Inertia.convert())
+            .to_request();
+
+        let page: InertiaPage =
+            actix_web::test::read_body_json(actix_web::test::call_service(&app, req).await).await;
+        visits.push(page.get_props()["visits"].clone());
+    }
+
+    assert_eq!(json!([1, 1, 2, 2]), json!(visits));
+}
+
+#[tokio::test]
+async fn test_request_aware_shared_props() {
+    let app = actix_web::test::init_service(generate_actix_app().await.wrap(
+        InertiaMiddleware::new().with_shared_props(Arc::new(|_req| {
+            let mut shared_props = HashMap::new();
+            shared_props.insert(
+                "auth".to_string(),
+                InertiaProp::Lazy(PropResolver::new_with_request(|ctx| {
+                    let req = ctx.request::<HttpRequest>().unwrap();
+                    let user = req.extensions().get::<AuthenticatedUser>().cloned();
+                    json!({ "user": user.map(|AuthenticatedUser(name)| name) })
+                })),
+            );
+
+            shared_props
+        })),
+    ))
+    .await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/authenticated")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let body = actix_web::test::call_service(&app, req)
+        .await
+        .into_body()
+        .try_into_bytes()
+        .unwrap();
+
+    let page: InertiaPage = serde_json::from_slice(&body[..]).unwrap();
+
+    assert_eq!(
+        &json!({ "user": "John Doe" }),
+        page.get_props().get("auth").unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_partial_reload() {
+    let app = actix_web::test::init_service(generate_actix_app().await).await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/partials")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .insert_header(("x-inertia-partial-component", "Partials"))
+        .insert_header(InertiaHeader::InertiaPartialData(vec!["foo", "baz"]).convert())
+        .to_request();
+
+    let body = actix_web::test::call_service(&app, req)
+        .await
+        .into_body()
+        .try_into_bytes()
+        .unwrap();
+
+    let page: InertiaPage = serde_json::from_slice(&body[..]).unwrap();
+
+    assert_eq!(
+        &json!({ "foo": "foo", "baz": "baz" }),
+        &json!(page.get_props())
+    );
+}
+
+#[tokio::test]
+async fn test_partial_reload_of_another_component() {
+    let app = actix_web::test::init_service(generate_actix_app().await).await;
+
+    // the partial reload has been requested for another component (e.g., the client has been
+    // redirected to this page), so it must be answered as a standard visit
+    let req = actix_web::test::TestRequest::get()
+        .uri("/partials")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .insert_header(("x-inertia-partial-component", "Index"))
+        .insert_header(InertiaHeader::InertiaPartialData(vec!["foo", "baz"]).convert())
+        .to_request();
+
+    let body = actix_web::test::call_service(&app, req)
+        .await
+        .into_body()
+        .try_into_bytes()
+        .unwrap();
+
+    let page: InertiaPage = serde_json::from_slice(&body[..]).unwrap();
+
+    assert_eq!(
+        &json!({ "foo": "foo", "bar": "bar" }),
+        &json!(page.get_props())
+    );
+}
+
 #[tokio::test]
 async fn test_inertia_route_service() {
     let app = actix_web::test::init_service(generate_actix_app().await).await;
@@ -314,6 +664,7 @@ async fn test_inertia_temporary_sessions() {
     request.extensions_mut().insert(InertiaTemporarySession {
         errors: Some(errors.clone()),
         prev_req_url: "/".into(),
+        clear_history: false,
     });
 
     // as wrong version has been set, it will force a refresh.
@@ -328,4 +679,503 @@ async fn test_inertia_temporary_sessions() {
     assert_eq!(&errors, storage.lock().unwrap()[0].errors.as_ref().unwrap());
 }
 
+#[tokio::test]
+async fn test_redirect_back() {
+    let app = actix_web::test::init_service(generate_actix_app().await).await;
+
+    let req = actix_web::test::TestRequest::post()
+        .uri("/back")
+        .insert_header(("Referer", "/signup"))
+        .to_request();
+
+    req.extensions_mut().insert(InertiaTemporarySession {
+        errors: None,
+        prev_req_url: "/users/create".into(),
+        clear_history: false,
+    });
+
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(StatusCode::SEE_OTHER, resp.status());
+    assert_eq!("/users/create", resp.headers().get("location").unwrap());
+
+    let req = actix_web::test::TestRequest::post()
+        .uri("/back")
+        .insert_header(("Referer", "/signup"))
+        .to_request();
+
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(StatusCode::SEE_OTHER, resp.status());
+    assert_eq!("/signup", resp.headers().get("location").unwrap());
+
+    let req = actix_web::test::TestRequest::post()
+        .uri("/back")
+        .to_request();
+
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(StatusCode::SEE_OTHER, resp.status());
+    assert_eq!("/", resp.headers().get("location").unwrap());
+}
+
+#[tokio::test]
+async fn test_redirect_with_flashed_data() {
+    let app = actix_web::test::init_service(generate_actix_app().await.wrap(
+        InertiaMiddleware::new().with_flash_store(Arc::new(SingleClientFlashStore::default())),
+    ))
+    .await;
+
+    let req = actix_web::test::TestRequest::post()
+        .uri("/signup")
+        .insert_header(("Referer", "/signup"))
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(StatusCode::SEE_OTHER, resp.status());
+    assert_eq!("/signup", resp.headers().get("location").unwrap());
+
+    // prefetch requests don't consume the flashed data
+    let req = actix_web::test::TestRequest::get()
+        .uri("/")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .insert_header(("Purpose", "prefetch"))
+        .to_request();
+
+    let page: InertiaPage =
+        actix_web::test::read_body_json(actix_web::test::call_service(&app, req).await).await;
+
+    assert_eq!(&json!({}), page.get_props().get("errors").unwrap());
+    assert!(page.get_props().get("flash").is_none());
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let page: InertiaPage =
+        actix_web::test::read_body_json(actix_web::test::call_service(&app, req).await).await;
+
+    assert_eq!(
+        &json!({"email": "This email is already taken."}),
+        page.get_props().get("errors").unwrap()
+    );
+    assert_eq!(
+        &json!({"warning": "Please, review your data."}),
+        page.get_props().get("flash").unwrap()
+    );
+
+    // flashed data only lasts for one request
+    let req = actix_web::test::TestRequest::get()
+        .uri("/")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let page: InertiaPage =
+        actix_web::test::read_body_json(actix_web::test::call_service(&app, req).await).await;
+
+    assert_eq!(&json!({}), page.get_props().get("errors").unwrap());
+    assert!(page.get_props().get("flash").is_none());
+}
+
+#[tokio::test]
+async fn test_external_redirect() {
+    let app = actix_web::test::init_service(generate_actix_app().await).await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/legacy")
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(StatusCode::CONFLICT, resp.status());
+    assert_eq!(
+        "https://inertiajs.com/legacy/index.php",
+        resp.headers().get("x-inertia-location").unwrap()
+    );
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/legacy")
+        .to_request();
+
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(StatusCode::FOUND, resp.status());
+    assert_eq!(
+        "https://inertiajs.com/legacy/index.php",
+        resp.headers().get("location").unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_custom_response_status_and_headers() {
+    let app = actix_web::test::init_service(generate_actix_app().await).await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/missing")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(StatusCode::NOT_FOUND, resp.status());
+    assert_eq!("no-store", resp.headers().get("cache-control").unwrap());
+    assert_eq!("true", resp.headers().get("x-inertia").unwrap());
+
+    let body = resp.into_body().try_into_bytes().unwrap();
+    let page: InertiaPage = serde_json::from_slice(&body).unwrap();
+    assert_eq!("Errors/NotFound", page.get_component().0);
+
+    // the custom header replaces the one set for prefetch requests
+    let req = actix_web::test::TestRequest::get()
+        .uri("/missing")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .insert_header(("Purpose", "prefetch"))
+        .to_request();
+
+    let resp = actix_web::test::call_service(&app, req).await;
+    let cache_control: Vec<_> = resp.headers().get_all("cache-control").collect();
+    assert_eq!(vec!["no-store"], cache_control);
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/missing")
+        .to_request();
+
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(StatusCode::NOT_FOUND, resp.status());
+    assert_eq!("no-store", resp.headers().get("cache-control").unwrap());
+    assert!(resp
+        .headers()
+        .get("content-type")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("text/html"));
+}
+
+#[tokio::test]
+async fn test_error_handlers() {
+    let app = actix_web::test::init_service(
+        App::new()
+            .wrap(InertiaErrorHandlers::<()>::new().build())
+            .app_data(Data::new(generate_inertia()))
+            .service(missing)
+            .service(failing),
+    )
+    .await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/unknown")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(StatusCode::NOT_FOUND, resp.status());
+
+    let body = resp.into_body().try_into_bytes().unwrap();
+    let page: InertiaPage = serde_json::from_slice(&body).unwrap();
+    assert_eq!("Errors/Show", page.get_component().0);
+    assert_eq!(json!(404), page.get_props()["status"]);
+    assert_eq!(json!("Not Found"), page.get_props()["message"]);
+
+    // in debug builds, the error's own message is rendered instead of the canonical reason
+    let req = actix_web::test::TestRequest::get()
+        .uri("/failing")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, resp.status());
+
+    let body = resp.into_body().try_into_bytes().unwrap();
+    let page: InertiaPage = serde_json::from_slice(&body).unwrap();
+    let expected_message = if cfg!(debug_assertions) {
+        "Inertia Error: Something went wrong."
+    } else {
+        "Internal Server Error"
+    };
+    assert_eq!(json!(expected_message), page.get_props()["message"]);
+
+    // messages given by the user are always rendered
+    let custom_app = actix_web::test::init_service(
+        App::new()
+            .wrap(
+                InertiaErrorHandlers::<()>::new()
+                    .with_status(StatusCode::INTERNAL_SERVER_ERROR, "Oops!")
+                    .build(),
+            )
+            .app_data(Data::new(generate_inertia()))
+            .service(failing),
+    )
+    .await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/failing")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let body = actix_web::test::call_service(&custom_app, req)
+        .await
+        .into_body()
+        .try_into_bytes()
+        .unwrap();
+    let page: InertiaPage = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json!("Oops!"), page.get_props()["message"]);
+
+    // pages rendered with a custom status are left untouched
+    let req = actix_web::test::TestRequest::get()
+        .uri("/missing")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let body = actix_web::test::call_service(&app, req)
+        .await
+        .into_body()
+        .try_into_bytes()
+        .unwrap();
+    let page: InertiaPage = serde_json::from_slice(&body).unwrap();
+    assert_eq!("Errors/NotFound", page.get_component().0);
+}
+
+#[tokio::test]
+async fn test_error_pages_keep_flashed_data() {
+    let app = actix_web::test::init_service(
+        generate_actix_app()
+            .await
+            .wrap(InertiaErrorHandlers::<()>::new().build())
+            .wrap(
+                InertiaMiddleware::new()
+                    .with_flash_store(Arc::new(SingleClientFlashStore::default())),
+            ),
+    )
+    .await;
+
+    let req = actix_web::test::TestRequest::post()
+        .uri("/signup")
+        .insert_header(("Referer", "/signup"))
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    actix_web::test::call_service(&app, req).await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/unknown")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(StatusCode::NOT_FOUND, resp.status());
+
+    let page: InertiaPage = actix_web::test::read_body_json(resp).await;
+    assert_eq!("Errors/Show", page.get_component().0);
+    assert!(page.get_props().get("flash").is_none());
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let page: InertiaPage =
+        actix_web::test::read_body_json(actix_web::test::call_service(&app, req).await).await;
+
+    assert_eq!(
+        &json!({"warning": "Please, review your data."}),
+        page.get_props().get("flash").unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_clear_history() {
+    let app = actix_web::test::init_service(generate_actix_app().await).await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let body = actix_web::test::call_service(&app, req)
+        .await
+        .into_body()
+        .try_into_bytes()
+        .unwrap();
+
+    let page: InertiaPage = serde_json::from_slice(&body[..]).unwrap();
+    assert!(!page.get_clear_history());
+    assert!(!page.get_encrypt_history());
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/loggedout")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let body = actix_web::test::call_service(&app, req)
+        .await
+        .into_body()
+        .try_into_bytes()
+        .unwrap();
+
+    let page: InertiaPage = serde_json::from_slice(&body[..]).unwrap();
+    assert!(page.get_clear_history());
+}
+
+#[tokio::test]
+async fn test_encrypt_history() {
+    let app = actix_web::test::init_service(
+        App::new()
+            .app_data(Data::new(generate_inertia_with(|config| {
+                config.encrypt_history()
+            })))
+            .service(home)
+            .service(public),
+    )
+    .await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let body = actix_web::test::call_service(&app, req)
+        .await
+        .into_body()
+        .try_into_bytes()
+        .unwrap();
+
+    let page: InertiaPage = serde_json::from_slice(&body[..]).unwrap();
+    assert!(page.get_encrypt_history());
+
+    // the per-response option overrides the global one
+    let req = actix_web::test::TestRequest::get()
+        .uri("/public")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let body = actix_web::test::call_service(&app, req)
+        .await
+        .into_body()
+        .try_into_bytes()
+        .unwrap();
+
+    let page: InertiaPage = serde_json::from_slice(&body[..]).unwrap();
+    assert!(!page.get_encrypt_history());
+}
+
+#[tokio::test]
+async fn test_errors_shared_prop() {
+    let app =
+        actix_web::test::init_service(generate_actix_app().await.wrap(InertiaMiddleware::new()))
+            .await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let body = actix_web::test::call_service(&app, req)
+        .await
+        .into_body()
+        .try_into_bytes()
+        .unwrap();
+
+    let page: InertiaPage = serde_json::from_slice(&body[..]).unwrap();
+    assert_eq!(&json!({}), page.get_props().get("errors").unwrap());
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .insert_header(("x-inertia-error-bag", "createUser"))
+        .to_request();
+
+    let mut errors = Map::new();
+    errors.insert("name".into(), "The name field is required.".into());
+
+    req.extensions_mut().insert(InertiaTemporarySession {
+        errors: Some(errors),
+        prev_req_url: "/".into(),
+        clear_history: false,
+    });
+
+    let body = actix_web::test::call_service(&app, req)
+        .await
+        .into_body()
+        .try_into_bytes()
+        .unwrap();
+
+    let page: InertiaPage = serde_json::from_slice(&body[..]).unwrap();
+    assert_eq!(
+        &json!({ "createUser": { "name": "The name field is required." } }),
+        page.get_props().get("errors").unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_errors_shared_prop_from_callback() {
+    let app = actix_web::test::init_service(generate_actix_app().await.wrap(
+        InertiaMiddleware::new().with_shared_props(Arc::new(|_req| {
+            let mut shared_props = HashMap::new();
+            shared_props.insert(
+                "errors".to_string(),
+                InertiaProp::Always(json!({ "account": "Your account is locked." })),
+            );
+            shared_props
+        })),
+    ))
+    .await;
+
+    // without session errors, the callback's errors prop is kept as it is
+    let req = actix_web::test::TestRequest::get()
+        .uri("/")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let page: InertiaPage =
+        actix_web::test::read_body_json(actix_web::test::call_service(&app, req).await).await;
+
+    assert_eq!(
+        &json!({ "account": "Your account is locked." }),
+        page.get_props().get("errors").unwrap()
+    );
+
+    // otherwise, the session errors are merged into it
+    let req = actix_web::test::TestRequest::get()
+        .uri("/")
+        .insert_header(InertiaHeader::Version(TEST_INERTIA_VERSION).convert())
+        .insert_header(InertiaHeader::Inertia.convert())
+        .to_request();
+
+    let mut errors = Map::new();
+    errors.insert("name".into(), "The name field is required.".into());
+
+    req.extensions_mut().insert(InertiaTemporarySession {
+        errors: Some(errors),
+        prev_req_url: "/".into(),
+        clear_history: false,
+    });
+
+    let page: InertiaPage =
+        actix_web::test::read_body_json(actix_web::test::call_service(&app, req).await).await;
+
+    assert_eq!(
+        &json!({
+            "account": "Your account is locked.",
+            "name": "The name field is required."
+        }),
+        page.get_props().get("errors").unwrap()
+    );
+}
+
 // endregion: --- Tests